@@ -1,9 +1,13 @@
 #![no_std]
 
 use soroban_sdk::{
-    contract, contracterror, contractimpl, contracttype, symbol_short, Address, Env, String, Vec,
+    contract, contracterror, contractimpl, contracttype, panic_with_error, symbol_short, token,
+    Address, Env, IntoVal, String, TryFromVal, Val, Vec,
 };
 
+/// Largest number of entries returned by a single history query
+const MAX_PAGE_SIZE: u32 = 50;
+
 #[contracterror]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
 #[repr(u32)]
@@ -17,16 +21,50 @@ pub enum Error {
     Overflow = 7,
     Underflow = 8,
     AlreadySettled = 9,
+    AlreadyInitialized = 10,
+    TransferFailed = 11,
+    Unauthorized = 12,
+    InsufficientBalance = 13,
+    NothingToClaim = 14,
+    NativeAssetNotConfigured = 15,
+    NotInitialized = 16,
 }
 
 /// Represents a supported asset type
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum Asset {
-    Native,
+    Native, // XLM, paid through the native token the admin sets
     Token(Address),
 }
 
+/// How a track's payouts reach its collaborators
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PayoutMode {
+    Push,       // Transfer each share straight to the recipient
+    Pull,       // Hold funds in the contract and credit claimable balances
+    PushOrHold, // Push, holding a refused share as claimable instead of failing
+}
+
+/// How `batch_distribute` treats a failing item
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BatchMode {
+    AllOrNothing, // Any failure reverts the whole batch
+    BestEffort,   // Failed items are skipped and reported
+}
+
+/// Outcome of a batch, published as the batch summary event
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BatchSummary {
+    pub succeeded: u32,
+    pub failed: u32,
+    pub distributed_amount: i128,
+    pub failed_amount: i128,
+}
+
 /// Collaborator split configuration
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -44,17 +82,60 @@ pub struct DistributionRecord {
     pub total_amount: i128,
     pub asset: Asset,
     pub distributions: Vec<(Address, i128)>,
+    pub residual_recipient: Address,
+    pub residual_amount: i128, // Unallocated basis points plus rounding dust
+    pub held: Vec<(Address, i128)>, // Shares held as claimable after a refused push
     pub timestamp: u64,
 }
 
+/// A page of a track's distribution history
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SettlementPage {
+    pub records: Vec<DistributionRecord>,
+    pub next_cursor: Option<u32>,
+}
+
+/// One payment to a collaborator, as seen from their side
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CollaboratorPayout {
+    pub track_id: String,
+    pub payout_id: String,
+    pub log_index: u32, // Index into the track's distribution log
+    pub asset: Asset,
+    pub amount: i128,
+    pub residual: bool, // Paid as the track's residual recipient
+    pub held: bool,     // Held as claimable after a refused push
+    pub timestamp: u64,
+}
+
+/// A page of a collaborator's payout history
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CollaboratorPayoutPage {
+    pub payouts: Vec<CollaboratorPayout>,
+    pub next_cursor: Option<u32>,
+}
+
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum DataKey {
     TrackSplits(String),
+    TrackOwner(String),           // track_id -> owner
+    ResidualRecipient(String),    // track_id -> residual recipient
+    PayoutMode(String),           // track_id -> PayoutMode
+    Claimable(Address, Address),  // collaborator, token contract -> claimable balance
     DistributionLog(String, u32), // track_id, index
     LogCount(String),             // track_id -> count
     GlobalLogCount,               // total distributions
     Settled(String),              // payout_id -> bool
+    CollabPayout(Address, u32),   // collaborator, index -> CollaboratorPayout
+    CollabPayoutCount(Address),   // collaborator -> count
+    TrackTotal(String, Asset),    // track_id, asset -> total distributed
+    CollabTotal(Address, Asset),  // collaborator, asset -> total earned
+    Admin,
+    NativeToken,                  // Stellar Asset Contract for XLM
 }
 
 #[contract]
@@ -62,12 +143,55 @@ pub struct AutoRoyaltyDistribution;
 
 #[contractimpl]
 impl AutoRoyaltyDistribution {
+    /// Set the admin and the Stellar Asset Contract used for native XLM payouts
+    pub fn initialize(env: Env, admin: Address, native_token: Address) -> Result<(), Error> {
+        if env.storage().instance().has(&DataKey::Admin) {
+            return Err(Error::AlreadyInitialized);
+        }
+        admin.require_auth();
+
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage().instance().set(&DataKey::NativeToken, &native_token);
+        Ok(())
+    }
+
+    /// Replace the Stellar Asset Contract used for native XLM payouts (admin only).
+    /// Balances already credited stay in the contract they were paid in and
+    /// are claimed with `Asset::Token` of that contract.
+    pub fn set_native_token(env: Env, native_token: Address) -> Result<(), Error> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(Error::NotInitialized)?;
+        admin.require_auth();
+
+        env.storage().instance().set(&DataKey::NativeToken, &native_token);
+        Ok(())
+    }
+
     /// Set up collaborator splits for a track. Percentages are in basis points (10000 = 100%).
+    /// The first caller to configure a new track becomes its owner; only the
+    /// owner can change the splits afterwards. Tracks whose splits predate
+    /// ownership are assigned an owner by the admin through
+    /// `assign_track_owner`.
     pub fn set_splits(
         env: Env,
+        owner: Address,
         track_id: String,
         collaborators: Vec<Collaborator>,
     ) -> Result<(), Error> {
+        owner.require_auth();
+        let owner_key = DataKey::TrackOwner(track_id.clone());
+        match env.storage().persistent().get::<_, Address>(&owner_key) {
+            Some(current) if current != owner => return Err(Error::Unauthorized),
+            Some(_) => {}
+            None if env.storage().persistent().has(&DataKey::TrackSplits(track_id.clone())) => {
+                return Err(Error::Unauthorized)
+            }
+            None => env.storage().persistent().set(&owner_key, &owner),
+        }
+
         if collaborators.is_empty() {
             return Err(Error::NoCollaborators);
         }
@@ -94,104 +218,216 @@ impl AutoRoyaltyDistribution {
         Ok(())
     }
 
-    /// Get split configuration for a track
-    pub fn get_splits(env: Env, track_id: String) -> Result<Vec<Collaborator>, Error> {
-        env.storage()
-            .persistent()
-            .get(&DataKey::TrackSplits(track_id))
-            .ok_or(Error::TrackNotFound)
+    /// Give a track that was configured before tracks had owners its owner
+    /// (admin only). Tracks that already have an owner are left alone.
+    pub fn assign_track_owner(env: Env, track_id: String, owner: Address) -> Result<(), Error> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(Error::NotInitialized)?;
+        admin.require_auth();
+
+        if !env.storage().persistent().has(&DataKey::TrackSplits(track_id.clone())) {
+            return Err(Error::TrackNotFound);
+        }
+        let owner_key = DataKey::TrackOwner(track_id.clone());
+        if env.storage().persistent().has(&owner_key) {
+            return Err(Error::Unauthorized);
+        }
+        env.storage().persistent().set(&owner_key, &owner);
+
+        env.events()
+            .publish((symbol_short!("splits"), symbol_short!("owner"), track_id), owner);
+
+        Ok(())
     }
 
-    /// Receive a tip/royalty and automatically distribute it among collaborators.
-    /// Includes payout_id for duplicate prevention.
-    pub fn receive_and_distribute(
+    /// Route unallocated basis points and rounding dust for a track to `recipient`
+    pub fn set_residual_recipient(
         env: Env,
+        owner: Address,
         track_id: String,
-        payout_id: String,
-        amount: i128,
-        asset: Asset,
-    ) -> Result<Vec<(Address, i128)>, Error> {
-        // Duplicate prevention
-        if env.storage().persistent().has(&DataKey::Settled(payout_id.clone())) {
-            return Err(Error::AlreadySettled);
+        recipient: Address,
+    ) -> Result<(), Error> {
+        owner.require_auth();
+        let current: Address = env
+            .storage()
+            .persistent()
+            .get(&DataKey::TrackOwner(track_id.clone()))
+            .ok_or(Error::TrackNotFound)?;
+        if current != owner {
+            return Err(Error::Unauthorized);
         }
 
-        if amount <= 0 {
-            return Err(Error::InvalidAmount);
-        }
+        env.storage()
+            .persistent()
+            .set(&DataKey::ResidualRecipient(track_id.clone()), &recipient);
+
+        env.events()
+            .publish((symbol_short!("splits"), symbol_short!("residual"), track_id), recipient);
 
-        let collaborators: Vec<Collaborator> = env
+        Ok(())
+    }
+
+    /// Choose whether a track pushes shares out on every payout or lets
+    /// collaborators claim them later. Tracks default to `Push`; `PushOrHold`
+    /// opts in to holding a refused share instead of failing the payout.
+    pub fn set_payout_mode(
+        env: Env,
+        owner: Address,
+        track_id: String,
+        mode: PayoutMode,
+    ) -> Result<(), Error> {
+        owner.require_auth();
+        let current: Address = env
             .storage()
             .persistent()
-            .get(&DataKey::TrackSplits(track_id.clone()))
+            .get(&DataKey::TrackOwner(track_id.clone()))
             .ok_or(Error::TrackNotFound)?;
+        if current != owner {
+            return Err(Error::Unauthorized);
+        }
 
-        let mut distributions: Vec<(Address, i128)> = Vec::new(&env);
-        let mut distributed: i128 = 0;
-        let count = collaborators.len();
+        env.storage()
+            .persistent()
+            .set(&DataKey::PayoutMode(track_id.clone()), &mode);
 
-        for i in 0..count {
-            let collab = collaborators.get(i).unwrap();
-            
-            let share = if i == (count - 1) {
-                // Last collaborator gets the remainder
-                amount.checked_sub(distributed).ok_or(Error::Underflow)?
-            } else {
-                amount
-                    .checked_mul(collab.percentage as i128)
-                    .ok_or(Error::Overflow)?
-                    .checked_div(10000)
-                    .ok_or(Error::Overflow)?
-            };
-
-            distributions.push_back((collab.address.clone(), share));
-            distributed = distributed
-                .checked_add(share)
-                .ok_or(Error::Overflow)?;
-        }
-
-        // Mark as settled
-        env.storage().persistent().set(&DataKey::Settled(payout_id.clone()), &true);
-
-        // Record history
-        let record = DistributionRecord {
-            track_id: track_id.clone(),
-            payout_id: payout_id.clone(),
-            total_amount: amount,
-            asset: asset.clone(),
-            distributions: distributions.clone(),
-            timestamp: env.ledger().timestamp(),
-        };
+        env.events()
+            .publish((symbol_short!("splits"), symbol_short!("mode"), track_id), mode);
+
+        Ok(())
+    }
+
+    /// Get the payout mode for a track
+    pub fn get_payout_mode(env: Env, track_id: String) -> PayoutMode {
+        payout_mode(&env, &track_id)
+    }
 
-        let log_idx: u32 = env.storage().persistent().get(&DataKey::LogCount(track_id.clone())).unwrap_or(0);
-        env.storage().persistent().set(&DataKey::DistributionLog(track_id.clone(), log_idx), &record);
-        env.storage().persistent().set(&DataKey::LogCount(track_id.clone()), &(log_idx + 1));
+    /// Withdraw everything credited to `collaborator` in `asset` by pull-mode
+    /// or held payouts. `Asset::Native` claims from the XLM contract currently
+    /// configured.
+    pub fn claim(env: Env, collaborator: Address, asset: Asset) -> Result<i128, Error> {
+        collaborator.require_auth();
+
+        let token_address = resolve_asset(&env, &asset)?;
+        let key = DataKey::Claimable(collaborator.clone(), token_address.clone());
+        let amount: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+        if amount <= 0 {
+            return Err(Error::NothingToClaim);
+        }
 
-        let global_count: u64 = env.storage().instance().get(&DataKey::GlobalLogCount).unwrap_or(0);
-        env.storage().instance().set(&DataKey::GlobalLogCount, &(global_count + 1));
+        env.storage().persistent().remove(&key);
+        token::Client::new(&env, &token_address).transfer(
+            &env.current_contract_address(),
+            &collaborator,
+            &amount,
+        );
 
-        // Emit distribution event
         env.events()
-            .publish((symbol_short!("royalty"), symbol_short!("dist"), payout_id), record);
+            .publish((symbol_short!("royalty"), symbol_short!("claim"), collaborator), (asset, amount));
 
-        Ok(distributions)
+        Ok(amount)
     }
 
-    /// Batch distribute royalties for multiple tracks.
-    /// Returns a vector of results for each distribution attempt.
+    /// Get the balance `collaborator` can claim in `asset`
+    pub fn get_claimable(env: Env, collaborator: Address, asset: Asset) -> i128 {
+        match resolve_asset(&env, &asset) {
+            Ok(token_address) => env
+                .storage()
+                .persistent()
+                .get(&DataKey::Claimable(collaborator, token_address))
+                .unwrap_or(0),
+            Err(_) => 0,
+        }
+    }
+
+    /// Get the owner of a track
+    pub fn get_track_owner(env: Env, track_id: String) -> Result<Address, Error> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::TrackOwner(track_id))
+            .ok_or(Error::TrackNotFound)
+    }
+
+    /// Get the residual recipient for a track, defaulting to the track owner
+    pub fn get_residual_recipient(env: Env, track_id: String) -> Result<Address, Error> {
+        residual_recipient(&env, &track_id)
+    }
+
+    /// Get split configuration for a track
+    pub fn get_splits(env: Env, track_id: String) -> Result<Vec<Collaborator>, Error> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::TrackSplits(track_id))
+            .ok_or(Error::TrackNotFound)
+    }
+
+    /// Receive a tip/royalty from `payer` and transfer it straight to the
+    /// collaborators, with anything their percentages leave over going to the
+    /// track's residual recipient. Includes payout_id for duplicate prevention.
+    /// The payer is charged in a single transfer. In `Push` mode a share the
+    /// token refuses to deliver fails the whole payout; tracks in `PushOrHold`
+    /// mode hold that share as claimable instead and flag it in the record.
+    pub fn receive_and_distribute(
+        env: Env,
+        payer: Address,
+        track_id: String,
+        payout_id: String,
+        amount: i128,
+        asset: Asset,
+    ) -> Result<DistributionRecord, Error> {
+        payer.require_auth();
+
+        settle_payout(&env, &payer, track_id, payout_id, amount, asset)
+    }
+
+    /// Batch distribute royalties for multiple tracks, all paid by `payer`.
+    /// Returns one code per item: 0 on success, otherwise the `Error`
+    /// discriminant. In `AllOrNothing` mode the first failure is returned and
+    /// the whole batch reverts. In `BestEffort` mode failed items are skipped
+    /// and reported, and the rest of the batch goes through. A share refused
+    /// on a `Push` track cannot be rolled back item by item, so it reverts the
+    /// batch with `TransferFailed` in either mode.
     pub fn batch_distribute(
         env: Env,
+        payer: Address,
         distributions: Vec<(String, String, i128, Asset)>,
-    ) -> Vec<bool> {
+        mode: BatchMode,
+    ) -> Result<Vec<u32>, Error> {
+        payer.require_auth();
+
         let mut results = Vec::new(&env);
+        let mut summary = BatchSummary {
+            succeeded: 0,
+            failed: 0,
+            distributed_amount: 0,
+            failed_amount: 0,
+        };
         for dist in distributions.iter() {
             let (track_id, payout_id, amount, asset) = dist;
-            match Self::receive_and_distribute(env.clone(), track_id, payout_id, amount, asset) {
-                Ok(_) => results.push_back(true),
-                Err(_) => results.push_back(false),
+            match settle_payout(&env, &payer, track_id, payout_id, amount, asset) {
+                Ok(_) => {
+                    results.push_back(0);
+                    summary.succeeded += 1;
+                    summary.distributed_amount = summary
+                        .distributed_amount
+                        .checked_add(amount)
+                        .ok_or(Error::Overflow)?;
+                }
+                Err(e) if mode == BatchMode::AllOrNothing => return Err(e),
+                Err(e) => {
+                    results.push_back(e as u32);
+                    summary.failed += 1;
+                    summary.failed_amount = summary.failed_amount.saturating_add(amount);
+                }
             }
         }
-        results
+
+        env.events()
+            .publish((symbol_short!("batch"), symbol_short!("summary"), payer), summary);
+
+        Ok(results)
     }
 
     /// Query settlement result for a specific track and index
@@ -203,7 +439,380 @@ impl AutoRoyaltyDistribution {
     pub fn get_settlement_count(env: Env, track_id: String) -> u32 {
         env.storage().persistent().get(&DataKey::LogCount(track_id)).unwrap_or(0)
     }
+
+    /// Get a track's settlements in log order, starting at index `cursor`
+    pub fn get_settlements(env: Env, track_id: String, cursor: u32, limit: u32) -> SettlementPage {
+        let total = log_count(&env, &track_id);
+        let (records, next_cursor) = collect_page(&env, cursor, limit, total, |index| {
+            env.storage()
+                .persistent()
+                .get(&DataKey::DistributionLog(track_id.clone(), index))
+        });
+        SettlementPage { records, next_cursor }
+    }
+
+    /// Get a track's settlements with `from <= timestamp <= to`, oldest
+    /// first. Pass 0 as `cursor` for the first page, then `next_cursor`.
+    pub fn get_settlements_by_time(
+        env: Env,
+        track_id: String,
+        from: u64,
+        to: u64,
+        cursor: u32,
+        limit: u32,
+    ) -> SettlementPage {
+        let mut records = Vec::new(&env);
+        let total = log_count(&env, &track_id);
+        let load = |index: u32| -> DistributionRecord {
+            env.storage()
+                .persistent()
+                .get(&DataKey::DistributionLog(track_id.clone(), index))
+                .unwrap()
+        };
+
+        // The log is appended in ledger order, so timestamps never decrease
+        let (mut lo, mut hi) = (0u32, total);
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            if load(mid).timestamp < from {
+                lo = mid + 1;
+            } else {
+                hi = mid;
+            }
+        }
+
+        let mut index = cursor.max(lo);
+        let limit = limit.min(MAX_PAGE_SIZE);
+        while index < total && records.len() < limit {
+            let record = load(index);
+            if record.timestamp > to {
+                return SettlementPage { records, next_cursor: None };
+            }
+            records.push_back(record);
+            index += 1;
+        }
+
+        let more = !records.is_empty() && index < total && load(index).timestamp <= to;
+        SettlementPage {
+            records,
+            next_cursor: if more { Some(index) } else { None },
+        }
+    }
+
+    /// Get everything paid to `collaborator`, oldest first, starting at `cursor`
+    pub fn get_collaborator_payouts(
+        env: Env,
+        collaborator: Address,
+        cursor: u32,
+        limit: u32,
+    ) -> CollaboratorPayoutPage {
+        let total: u32 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::CollabPayoutCount(collaborator.clone()))
+            .unwrap_or(0);
+        let (payouts, next_cursor) = collect_page(&env, cursor, limit, total, |index| {
+            env.storage()
+                .persistent()
+                .get(&DataKey::CollabPayout(collaborator.clone(), index))
+        });
+        CollaboratorPayoutPage { payouts, next_cursor }
+    }
+
+    /// Get the total distributed for a track in `asset`
+    pub fn get_track_total(env: Env, track_id: String, asset: Asset) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::TrackTotal(track_id, asset))
+            .unwrap_or(0)
+    }
+
+    /// Get the total earned by a collaborator in `asset` across all tracks
+    pub fn get_collaborator_total(env: Env, collaborator: Address, asset: Asset) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::CollabTotal(collaborator, asset))
+            .unwrap_or(0)
+    }
+
+    /// Get total settlement count across all tracks
+    pub fn get_distribution_count(env: Env) -> u64 {
+        env.storage().instance().get(&DataKey::GlobalLogCount).unwrap_or(0)
+    }
+}
+
+/// Split `amount` across the track's collaborators, pay each share from
+/// `payer` (or credit it as claimable for pull-mode tracks), then mark the
+/// payout settled and log it.
+fn settle_payout(
+    env: &Env,
+    payer: &Address,
+    track_id: String,
+    payout_id: String,
+    amount: i128,
+    asset: Asset,
+) -> Result<DistributionRecord, Error> {
+    // Duplicate prevention
+    if env.storage().persistent().has(&DataKey::Settled(payout_id.clone())) {
+        return Err(Error::AlreadySettled);
+    }
+
+    if amount <= 0 {
+        return Err(Error::InvalidAmount);
+    }
+    let token_address = resolve_asset(env, &asset)?;
+
+    let collaborators: Vec<Collaborator> = env
+        .storage()
+        .persistent()
+        .get(&DataKey::TrackSplits(track_id.clone()))
+        .ok_or(Error::TrackNotFound)?;
+
+    let mut distributions: Vec<(Address, i128)> = Vec::new(env);
+    let mut distributed: i128 = 0;
+
+    for collab in collaborators.iter() {
+        let share = amount
+            .checked_mul(collab.percentage as i128)
+            .ok_or(Error::Overflow)?
+            .checked_div(10000)
+            .ok_or(Error::Overflow)?;
+
+        distributions.push_back((collab.address.clone(), share));
+        distributed = distributed
+            .checked_add(share)
+            .ok_or(Error::Overflow)?;
+    }
+
+    // Unallocated basis points and rounding dust
+    let residual_amount = amount.checked_sub(distributed).ok_or(Error::Underflow)?;
+    let residual_recipient = residual_recipient(env, &track_id)?;
+
+    // Take the whole amount in one transfer, so a failure here leaves
+    // nothing half-paid
+    let token_client = token::Client::new(env, &token_address);
+    if token_client.balance(payer) < amount {
+        return Err(Error::InsufficientBalance);
+    }
+    match token_client.try_transfer(payer, &env.current_contract_address(), &amount) {
+        Ok(Ok(())) => {}
+        _ => return Err(Error::TransferFailed),
+    }
+
+    let mut held = Vec::new(env);
+    match payout_mode(env, &track_id) {
+        PayoutMode::Push => {
+            for (recipient, share) in distributions.iter() {
+                push_share(env, &token_client, &recipient, share);
+            }
+            push_share(env, &token_client, &residual_recipient, residual_amount);
+        }
+        PayoutMode::PushOrHold => {
+            for (recipient, share) in distributions.iter() {
+                if pay_or_hold(env, &token_client, &recipient, &asset, share)? {
+                    held.push_back((recipient, share));
+                }
+            }
+            if pay_or_hold(env, &token_client, &residual_recipient, &asset, residual_amount)? {
+                held.push_back((residual_recipient.clone(), residual_amount));
+            }
+        }
+        PayoutMode::Pull => {
+            for (recipient, share) in distributions.iter() {
+                credit_claimable(env, &recipient, &token_address, share)?;
+            }
+            credit_claimable(env, &residual_recipient, &token_address, residual_amount)?;
+        }
+    }
+
+    // Mark as settled
+    env.storage().persistent().set(&DataKey::Settled(payout_id.clone()), &true);
+
+    // Record history
+    let record = DistributionRecord {
+        track_id: track_id.clone(),
+        payout_id: payout_id.clone(),
+        total_amount: amount,
+        asset: asset.clone(),
+        distributions,
+        residual_recipient,
+        residual_amount,
+        held,
+        timestamp: env.ledger().timestamp(),
+    };
+
+    let log_idx = log_count(env, &track_id);
+    env.storage().persistent().set(&DataKey::DistributionLog(track_id.clone(), log_idx), &record);
+    env.storage().persistent().set(&DataKey::LogCount(track_id.clone()), &(log_idx + 1));
+    add_total(env, &DataKey::TrackTotal(track_id.clone(), asset.clone()), amount)?;
+
+    // Index each payment under its recipient
+    for (recipient, share) in record.distributions.iter() {
+        record_collab_payout(env, &recipient, &record, log_idx, share, false)?;
+    }
+    record_collab_payout(env, &record.residual_recipient, &record, log_idx, residual_amount, true)?;
+
+    let global_count: u64 = env.storage().instance().get(&DataKey::GlobalLogCount).unwrap_or(0);
+    env.storage().instance().set(&DataKey::GlobalLogCount, &(global_count + 1));
+
+    // Emit distribution event
+    env.events()
+        .publish((symbol_short!("royalty"), symbol_short!("dist"), payout_id), record.clone());
+
+    Ok(record)
+}
+
+/// Residual recipient for a track, falling back to the track owner.
+fn residual_recipient(env: &Env, track_id: &String) -> Result<Address, Error> {
+    if let Some(recipient) = env
+        .storage()
+        .persistent()
+        .get(&DataKey::ResidualRecipient(track_id.clone()))
+    {
+        return Ok(recipient);
+    }
+    env.storage()
+        .persistent()
+        .get(&DataKey::TrackOwner(track_id.clone()))
+        .ok_or(Error::TrackNotFound)
+}
+
+/// Number of settlements logged for a track.
+fn log_count(env: &Env, track_id: &String) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::LogCount(track_id.clone()))
+        .unwrap_or(0)
+}
+
+/// Add `amount` to the running total stored under `key`.
+fn add_total(env: &Env, key: &DataKey, amount: i128) -> Result<(), Error> {
+    let total: i128 = env.storage().persistent().get(key).unwrap_or(0);
+    let total = total.checked_add(amount).ok_or(Error::Overflow)?;
+    env.storage().persistent().set(key, &total);
+    Ok(())
+}
+
+/// Append a payment to `recipient`'s payout index and earnings total.
+fn record_collab_payout(
+    env: &Env,
+    recipient: &Address,
+    record: &DistributionRecord,
+    log_index: u32,
+    amount: i128,
+    residual: bool,
+) -> Result<(), Error> {
+    if amount <= 0 {
+        return Ok(());
+    }
+    let count_key = DataKey::CollabPayoutCount(recipient.clone());
+    let index: u32 = env.storage().persistent().get(&count_key).unwrap_or(0);
+    let payout = CollaboratorPayout {
+        track_id: record.track_id.clone(),
+        payout_id: record.payout_id.clone(),
+        log_index,
+        asset: record.asset.clone(),
+        amount,
+        residual,
+        held: record.held.contains((recipient.clone(), amount)),
+        timestamp: record.timestamp,
+    };
+    env.storage()
+        .persistent()
+        .set(&DataKey::CollabPayout(recipient.clone(), index), &payout);
+    env.storage().persistent().set(&count_key, &(index + 1));
+    add_total(env, &DataKey::CollabTotal(recipient.clone(), record.asset.clone()), amount)
+}
+
+/// Load entries `cursor..cursor + limit` of a `total`-long index, at most
+/// `MAX_PAGE_SIZE` at a time. The next cursor is only set when this page read
+/// something and more entries remain.
+fn collect_page<T, F>(env: &Env, cursor: u32, limit: u32, total: u32, load: F) -> (Vec<T>, Option<u32>)
+where
+    T: IntoVal<Env, Val> + TryFromVal<Env, Val>,
+    F: Fn(u32) -> Option<T>,
+{
+    let end = total.min(cursor.saturating_add(limit.min(MAX_PAGE_SIZE)));
+    let mut items = Vec::new(env);
+    let mut index = cursor;
+    while index < end {
+        if let Some(item) = load(index) {
+            items.push_back(item);
+        }
+        index += 1;
+    }
+
+    (items, if cursor < end && end < total { Some(end) } else { None })
+}
+
+/// Payout mode for a track, defaulting to push.
+fn payout_mode(env: &Env, track_id: &String) -> PayoutMode {
+    env.storage()
+        .persistent()
+        .get(&DataKey::PayoutMode(track_id.clone()))
+        .unwrap_or(PayoutMode::Push)
+}
+
+/// Send `amount` held by the contract to `recipient`. Earlier shares of the
+/// payout may already have gone out, so a refused transfer aborts the whole
+/// invocation rather than leaving the payout half-paid.
+fn push_share(env: &Env, token_client: &token::Client, recipient: &Address, amount: i128) {
+    if amount <= 0 {
+        return;
+    }
+    match token_client.try_transfer(&env.current_contract_address(), recipient, &amount) {
+        Ok(Ok(())) => {}
+        _ => panic_with_error!(env, Error::TransferFailed),
+    }
+}
+
+/// Send `amount` held by the contract to `recipient`. If the token rejects
+/// the transfer (for example a frozen or missing trustline), the share is
+/// credited as claimable instead and `true` is returned.
+fn pay_or_hold(
+    env: &Env,
+    token_client: &token::Client,
+    recipient: &Address,
+    asset: &Asset,
+    amount: i128,
+) -> Result<bool, Error> {
+    if amount <= 0 {
+        return Ok(false);
+    }
+    match token_client.try_transfer(&env.current_contract_address(), recipient, &amount) {
+        Ok(Ok(())) => Ok(false),
+        _ => {
+            credit_claimable(env, recipient, &token_client.address, amount)?;
+            env.events()
+                .publish((symbol_short!("royalty"), symbol_short!("held"), recipient.clone()), (asset.clone(), amount));
+            Ok(true)
+        }
+    }
+}
+
+/// Add `amount` to what `recipient` can claim from `token_address`.
+fn credit_claimable(env: &Env, recipient: &Address, token_address: &Address, amount: i128) -> Result<(), Error> {
+    if amount <= 0 {
+        return Ok(());
+    }
+    let key = DataKey::Claimable(recipient.clone(), token_address.clone());
+    let balance: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+    let balance = balance.checked_add(amount).ok_or(Error::Overflow)?;
+    env.storage().persistent().set(&key, &balance);
+    Ok(())
+}
+
+/// Token contract payouts in `asset` are drawn from. Native XLM has no
+/// address of its own here, so it needs the admin to have set one.
+fn resolve_asset(env: &Env, asset: &Asset) -> Result<Address, Error> {
+    match asset {
+        Asset::Native => env
+            .storage()
+            .instance()
+            .get(&DataKey::NativeToken)
+            .ok_or(Error::NativeAssetNotConfigured),
+        Asset::Token(token_address) => Ok(token_address.clone()),
+    }
 }
 
 mod test;
-;
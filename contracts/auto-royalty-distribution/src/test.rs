@@ -1,13 +1,31 @@
 #![cfg(test)]
 
 use super::*;
-use soroban_sdk::{testutils::Address as _, Address, Env, String, Vec};
+use soroban_sdk::{
+    symbol_short, testutils::{Address as _, Events, IssuerFlags, Ledger}, token, Address, Env, String, Symbol,
+    TryFromVal, Vec,
+};
+
+/// Register a Stellar Asset Contract as the native XLM token, initialize the
+/// contract with it and return a funded payer alongside the token address.
+fn setup_native(env: &Env, client: &AutoRoyaltyDistributionClient) -> (Address, Address) {
+    let xlm = env
+        .register_stellar_asset_contract_v2(Address::generate(env))
+        .address();
+    let payer = Address::generate(env);
+    token::StellarAssetClient::new(env, &xlm).mint(&payer, &1_000_000);
+    client.initialize(&Address::generate(env), &xlm);
+    (payer, xlm)
+}
 
 #[test]
 fn test_set_splits() {
     let env = Env::default();
+    env.mock_all_auths();
     let contract_id = env.register_contract(None, AutoRoyaltyDistribution);
     let client = AutoRoyaltyDistributionClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    setup_native(&env, &client);
 
     let track_id = String::from_str(&env, "track_001");
     let collab1 = Address::generate(&env);
@@ -23,7 +41,7 @@ fn test_set_splits() {
         percentage: 4000, // 40%
     });
 
-    client.set_splits(&track_id, &collabs);
+    client.set_splits(&owner, &track_id, &collabs);
 
     let retrieved = client.get_splits(&track_id);
     assert_eq!(retrieved.len(), 2);
@@ -34,8 +52,11 @@ fn test_set_splits() {
 #[test]
 fn test_receive_and_distribute() {
     let env = Env::default();
+    env.mock_all_auths();
     let contract_id = env.register_contract(None, AutoRoyaltyDistribution);
     let client = AutoRoyaltyDistributionClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    let (payer, xlm) = setup_native(&env, &client);
 
     let track_id = String::from_str(&env, "track_dist");
     let collab1 = Address::generate(&env);
@@ -51,20 +72,28 @@ fn test_receive_and_distribute() {
         percentage: 3000, // 30%
     });
 
-    client.set_splits(&track_id, &collabs);
+    client.set_splits(&owner, &track_id, &collabs);
+
+    let result = client.receive_and_distribute(&payer, &track_id, &String::from_str(&env, "payout_1"), &1000, &Asset::Native);
 
-    let result = client.receive_and_distribute(&track_id, &1000, &Asset::Native);
+    assert_eq!(result.distributions.len(), 2);
+    assert_eq!(result.distributions.get(0).unwrap(), (collab1.clone(), 700));
+    assert_eq!(result.distributions.get(1).unwrap(), (collab2.clone(), 300));
 
-    assert_eq!(result.len(), 2);
-    assert_eq!(result.get(0).unwrap(), (collab1.clone(), 700));
-    assert_eq!(result.get(1).unwrap(), (collab2.clone(), 300));
+    let xlm_client = token::Client::new(&env, &xlm);
+    assert_eq!(xlm_client.balance(&collab1), 700);
+    assert_eq!(xlm_client.balance(&collab2), 300);
+    assert_eq!(xlm_client.balance(&payer), 1_000_000 - 1000);
 }
 
 #[test]
 fn test_rounding_no_loss() {
     let env = Env::default();
+    env.mock_all_auths();
     let contract_id = env.register_contract(None, AutoRoyaltyDistribution);
     let client = AutoRoyaltyDistributionClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    let (payer, _) = setup_native(&env, &client);
 
     let track_id = String::from_str(&env, "track_round");
     let collab1 = Address::generate(&env);
@@ -85,28 +114,35 @@ fn test_rounding_no_loss() {
         percentage: 3334, // 33.34%
     });
 
-    client.set_splits(&track_id, &collabs);
+    client.set_splits(&owner, &track_id, &collabs);
 
     let result = client.receive_and_distribute(
+        &payer,
         &track_id,
-        &100, // Small amount to trigger rounding
+        &String::from_str(&env, "payout_2"), &100, // Small amount to trigger rounding
         &Asset::Native,
     );
 
-    // Verify no funds are lost: sum of distributions must equal original amount
+    // Verify no funds are lost: shares plus residual must equal original amount
     let mut total: i128 = 0;
-    for dist in result.iter() {
+    for dist in result.distributions.iter() {
         let (_, amount) = dist;
         total += amount;
     }
-    assert_eq!(total, 100);
+    assert_eq!(total, 99);
+    assert_eq!(result.residual_recipient, owner);
+    assert_eq!(result.residual_amount, 1);
+    assert_eq!(result.distributions.get(2).unwrap(), (collab3, 33));
 }
 
 #[test]
 fn test_multiple_assets() {
     let env = Env::default();
+    env.mock_all_auths();
     let contract_id = env.register_contract(None, AutoRoyaltyDistribution);
     let client = AutoRoyaltyDistributionClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    let (payer, xlm) = setup_native(&env, &client);
 
     let track_id = String::from_str(&env, "track_multi_asset");
     let collab1 = Address::generate(&env);
@@ -117,23 +153,32 @@ fn test_multiple_assets() {
         percentage: 10000, // 100%
     });
 
-    client.set_splits(&track_id, &collabs);
+    client.set_splits(&owner, &track_id, &collabs);
 
     // Test with Native asset
-    let result_native = client.receive_and_distribute(&track_id, &500, &Asset::Native);
-    assert_eq!(result_native.get(0).unwrap(), (collab1.clone(), 500));
+    let result_native = client.receive_and_distribute(&payer, &track_id, &String::from_str(&env, "payout_3"), &500, &Asset::Native);
+    assert_eq!(result_native.distributions.get(0).unwrap(), (collab1.clone(), 500));
 
     // Test with Token asset
-    let token_addr = Address::generate(&env);
-    let result_token = client.receive_and_distribute(&track_id, &750, &Asset::Token(token_addr));
-    assert_eq!(result_token.get(0).unwrap(), (collab1.clone(), 750));
+    let token_addr = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    token::StellarAssetClient::new(&env, &token_addr).mint(&payer, &750);
+    let result_token = client.receive_and_distribute(&payer, &track_id, &String::from_str(&env, "payout_4"), &750, &Asset::Token(token_addr.clone()));
+    assert_eq!(result_token.distributions.get(0).unwrap(), (collab1.clone(), 750));
+
+    assert_eq!(token::Client::new(&env, &xlm).balance(&collab1), 500);
+    assert_eq!(token::Client::new(&env, &token_addr).balance(&collab1), 750);
 }
 
 #[test]
 fn test_batch_distribute() {
     let env = Env::default();
+    env.mock_all_auths();
     let contract_id = env.register_contract(None, AutoRoyaltyDistribution);
     let client = AutoRoyaltyDistributionClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    let (payer, _) = setup_native(&env, &client);
 
     let track1 = String::from_str(&env, "track_batch1");
     let track2 = String::from_str(&env, "track_batch2");
@@ -145,23 +190,148 @@ fn test_batch_distribute() {
         percentage: 10000,
     });
 
-    client.set_splits(&track1, &collabs);
-    client.set_splits(&track2, &collabs);
+    client.set_splits(&owner, &track1, &collabs);
+    client.set_splits(&owner, &track2, &collabs);
 
     let mut batch = Vec::new(&env);
-    batch.push_back((track1, 1000_i128, Asset::Native));
-    batch.push_back((track2, 2000_i128, Asset::Native));
+    batch.push_back((track1, String::from_str(&env, "batch_1"), 1000_i128, Asset::Native));
+    batch.push_back((track2, String::from_str(&env, "batch_2"), 2000_i128, Asset::Native));
 
-    client.batch_distribute(&batch);
+    let results = client.batch_distribute(&payer, &batch, &BatchMode::AllOrNothing);
 
+    assert_eq!(results, Vec::from_array(&env, [0, 0]));
     assert_eq!(client.get_distribution_count(), 2);
 }
 
+type BatchItem = (String, String, i128, Asset);
+
+fn setup_batch(env: &Env, client: &AutoRoyaltyDistributionClient) -> (Address, Address, Vec<BatchItem>) {
+    let owner = Address::generate(env);
+    let (payer, xlm) = setup_native(env, client);
+    let collab = Address::generate(env);
+    let mut collabs = Vec::new(env);
+    collabs.push_back(Collaborator {
+        address: collab.clone(),
+        percentage: 10000,
+    });
+    let track = String::from_str(env, "track_batch");
+    client.set_splits(&owner, &track, &collabs);
+
+    let mut batch = Vec::new(env);
+    batch.push_back((track.clone(), String::from_str(env, "b_ok"), 1000_i128, Asset::Native));
+    batch.push_back((String::from_str(env, "missing"), String::from_str(env, "b_missing"), 500_i128, Asset::Native));
+    batch.push_back((track.clone(), String::from_str(env, "b_zero"), 0_i128, Asset::Native));
+    batch.push_back((track, String::from_str(env, "b_ok2"), 2000_i128, Asset::Native));
+    (payer, xlm, batch)
+}
+
+#[test]
+fn test_batch_all_or_nothing_reverts_on_failure() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, AutoRoyaltyDistribution);
+    let client = AutoRoyaltyDistributionClient::new(&env, &contract_id);
+    let (payer, xlm, batch) = setup_batch(&env, &client);
+
+    let result = client.try_batch_distribute(&payer, &batch, &BatchMode::AllOrNothing);
+    assert_eq!(result, Err(Ok(Error::TrackNotFound)));
+    assert_eq!(client.get_distribution_count(), 0);
+    assert_eq!(token::Client::new(&env, &xlm).balance(&payer), 1_000_000);
+}
+
+#[test]
+fn test_batch_all_or_nothing_reverts_on_frozen_collaborator() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, AutoRoyaltyDistribution);
+    let client = AutoRoyaltyDistributionClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    let payer = Address::generate(&env);
+    let sac = env.register_stellar_asset_contract_v2(Address::generate(&env));
+    sac.issuer().set_flag(IssuerFlags::RevocableFlag);
+    let usdc = sac.address();
+    token::StellarAssetClient::new(&env, &usdc).mint(&payer, &1_000_000);
+
+    let open = Address::generate(&env);
+    let frozen = Address::generate(&env);
+    let open_track = String::from_str(&env, "track_open");
+    let frozen_track = String::from_str(&env, "track_frozen");
+    let open_splits = Vec::from_array(
+        &env,
+        [Collaborator {
+            address: open.clone(),
+            percentage: 10000,
+        }],
+    );
+    client.set_splits(&owner, &open_track, &open_splits);
+    let frozen_splits = Vec::from_array(
+        &env,
+        [Collaborator {
+            address: frozen.clone(),
+            percentage: 10000,
+        }],
+    );
+    client.set_splits(&owner, &frozen_track, &frozen_splits);
+    token::StellarAssetClient::new(&env, &usdc).set_authorized(&frozen, &false);
+
+    let mut batch = Vec::new(&env);
+    batch.push_back((open_track.clone(), String::from_str(&env, "b_open"), 1000_i128, Asset::Token(usdc.clone())));
+    batch.push_back((frozen_track.clone(), String::from_str(&env, "b_frozen"), 1000_i128, Asset::Token(usdc.clone())));
+    let result = client.try_batch_distribute(&payer, &batch, &BatchMode::AllOrNothing);
+    assert_eq!(result, Err(Ok(Error::TransferFailed)));
+
+    // The item before the refused one is rolled back too
+    let usdc_client = token::Client::new(&env, &usdc);
+    assert_eq!(usdc_client.balance(&payer), 1_000_000);
+    assert_eq!(usdc_client.balance(&open), 0);
+    assert_eq!(usdc_client.balance(&contract_id), 0);
+    assert_eq!(client.get_settlement_count(&open_track), 0);
+    assert_eq!(client.get_settlement_count(&frozen_track), 0);
+    assert_eq!(client.get_distribution_count(), 0);
+}
+
+#[test]
+fn test_batch_best_effort_reports_failures() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, AutoRoyaltyDistribution);
+    let client = AutoRoyaltyDistributionClient::new(&env, &contract_id);
+    let (payer, xlm, batch) = setup_batch(&env, &client);
+
+    let results = client.batch_distribute(&payer, &batch, &BatchMode::BestEffort);
+    assert_eq!(
+        results,
+        Vec::from_array(
+            &env,
+            [0, Error::TrackNotFound as u32, Error::InvalidAmount as u32, 0]
+        )
+    );
+    assert_eq!(client.get_distribution_count(), 2);
+    assert_eq!(token::Client::new(&env, &xlm).balance(&payer), 1_000_000 - 3000);
+
+    let (_, topics, data) = env.events().all().last().unwrap();
+    let topic = Symbol::try_from_val(&env, &topics.get(1).unwrap()).unwrap();
+    assert_eq!(topic, symbol_short!("summary"));
+    let summary = BatchSummary::try_from_val(&env, &data).unwrap();
+    assert_eq!(
+        summary,
+        BatchSummary {
+            succeeded: 2,
+            failed: 2,
+            distributed_amount: 3000,
+            failed_amount: 500,
+        }
+    );
+}
+
 #[test]
 fn test_invalid_percentage() {
     let env = Env::default();
+    env.mock_all_auths();
     let contract_id = env.register_contract(None, AutoRoyaltyDistribution);
     let client = AutoRoyaltyDistributionClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    setup_native(&env, &client);
 
     let track_id = String::from_str(&env, "track_invalid");
     let collab1 = Address::generate(&env);
@@ -172,15 +342,18 @@ fn test_invalid_percentage() {
         percentage: 0, // Invalid: 0%
     });
 
-    let result = client.try_set_splits(&track_id, &collabs);
+    let result = client.try_set_splits(&owner, &track_id, &collabs);
     assert_eq!(result, Err(Ok(Error::InvalidPercentage)));
 }
 
 #[test]
 fn test_total_exceeds_100() {
     let env = Env::default();
+    env.mock_all_auths();
     let contract_id = env.register_contract(None, AutoRoyaltyDistribution);
     let client = AutoRoyaltyDistributionClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    setup_native(&env, &client);
 
     let track_id = String::from_str(&env, "track_over100");
     let collab1 = Address::generate(&env);
@@ -196,26 +369,31 @@ fn test_total_exceeds_100() {
         percentage: 5000,
     });
 
-    let result = client.try_set_splits(&track_id, &collabs);
-    assert_eq!(result, Err(Ok(Error::TotalExceeds100)));
+    let result = client.try_set_splits(&owner, &track_id, &collabs);
+    assert_eq!(result, Err(Ok(Error::TotalExceeds10000)));
 }
 
 #[test]
 fn test_track_not_found() {
     let env = Env::default();
+    env.mock_all_auths();
     let contract_id = env.register_contract(None, AutoRoyaltyDistribution);
     let client = AutoRoyaltyDistributionClient::new(&env, &contract_id);
+    let (payer, _) = setup_native(&env, &client);
 
     let track_id = String::from_str(&env, "nonexistent");
-    let result = client.try_receive_and_distribute(&track_id, &1000, &Asset::Native);
+    let result = client.try_receive_and_distribute(&payer, &track_id, &String::from_str(&env, "payout_5"), &1000, &Asset::Native);
     assert_eq!(result, Err(Ok(Error::TrackNotFound)));
 }
 
 #[test]
 fn test_invalid_amount() {
     let env = Env::default();
+    env.mock_all_auths();
     let contract_id = env.register_contract(None, AutoRoyaltyDistribution);
     let client = AutoRoyaltyDistributionClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    let (payer, _) = setup_native(&env, &client);
 
     let track_id = String::from_str(&env, "track_inv_amt");
     let collab1 = Address::generate(&env);
@@ -226,24 +404,467 @@ fn test_invalid_amount() {
         percentage: 10000,
     });
 
-    client.set_splits(&track_id, &collabs);
+    client.set_splits(&owner, &track_id, &collabs);
 
-    let result = client.try_receive_and_distribute(&track_id, &0, &Asset::Native);
+    let result = client.try_receive_and_distribute(&payer, &track_id, &String::from_str(&env, "payout_6"), &0, &Asset::Native);
     assert_eq!(result, Err(Ok(Error::InvalidAmount)));
 
-    let result = client.try_receive_and_distribute(&track_id, &-100, &Asset::Native);
+    let result = client.try_receive_and_distribute(&payer, &track_id, &String::from_str(&env, "payout_7"), &-100, &Asset::Native);
     assert_eq!(result, Err(Ok(Error::InvalidAmount)));
 }
 
 #[test]
 fn test_no_collaborators() {
     let env = Env::default();
+    env.mock_all_auths();
     let contract_id = env.register_contract(None, AutoRoyaltyDistribution);
     let client = AutoRoyaltyDistributionClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    setup_native(&env, &client);
 
     let track_id = String::from_str(&env, "track_empty");
     let collabs: Vec<Collaborator> = Vec::new(&env);
 
-    let result = client.try_set_splits(&track_id, &collabs);
+    let result = client.try_set_splits(&owner, &track_id, &collabs);
     assert_eq!(result, Err(Ok(Error::NoCollaborators)));
 }
+
+#[test]
+fn test_native_asset_requires_configuration() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, AutoRoyaltyDistribution);
+    let client = AutoRoyaltyDistributionClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+
+    let track_id = String::from_str(&env, "track_native");
+    let mut collabs = Vec::new(&env);
+    collabs.push_back(Collaborator {
+        address: Address::generate(&env),
+        percentage: 10000,
+    });
+    client.set_splits(&owner, &track_id, &collabs);
+
+    let payer = Address::generate(&env);
+    let payout_id = String::from_str(&env, "payout_native");
+    let result = client.try_receive_and_distribute(&payer, &track_id, &payout_id, &1000, &Asset::Native);
+    assert_eq!(result, Err(Ok(Error::NativeAssetNotConfigured)));
+
+    let result = client.try_set_native_token(&Address::generate(&env));
+    assert_eq!(result, Err(Ok(Error::NotInitialized)));
+}
+
+#[test]
+fn test_admin_replaces_native_token() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, AutoRoyaltyDistribution);
+    let client = AutoRoyaltyDistributionClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    let (payer, xlm) = setup_native(&env, &client);
+
+    let track_id = String::from_str(&env, "track_new_xlm");
+    let collab = Address::generate(&env);
+    let mut collabs = Vec::new(&env);
+    collabs.push_back(Collaborator {
+        address: collab.clone(),
+        percentage: 10000,
+    });
+    client.set_splits(&owner, &track_id, &collabs);
+
+    // Credit a claimable balance in the original XLM contract
+    client.set_payout_mode(&owner, &track_id, &PayoutMode::Pull);
+    client.receive_and_distribute(&payer, &track_id, &String::from_str(&env, "payout_old_xlm"), &300, &Asset::Native);
+
+    let new_xlm = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    token::StellarAssetClient::new(&env, &new_xlm).mint(&payer, &1000);
+    token::StellarAssetClient::new(&env, &new_xlm).mint(&contract_id, &1000);
+    client.set_native_token(&new_xlm);
+
+    // The earlier credit is still owed in the old contract, not the new one
+    assert_eq!(client.get_claimable(&collab, &Asset::Native), 0);
+    assert_eq!(client.get_claimable(&collab, &Asset::Token(xlm.clone())), 300);
+    assert_eq!(client.try_claim(&collab, &Asset::Native), Err(Ok(Error::NothingToClaim)));
+    client.claim(&collab, &Asset::Token(xlm.clone()));
+    assert_eq!(token::Client::new(&env, &xlm).balance(&collab), 300);
+    assert_eq!(token::Client::new(&env, &new_xlm).balance(&contract_id), 1000);
+
+    client.set_payout_mode(&owner, &track_id, &PayoutMode::Push);
+    client.receive_and_distribute(&payer, &track_id, &String::from_str(&env, "payout_new_xlm"), &1000, &Asset::Native);
+    assert_eq!(token::Client::new(&env, &new_xlm).balance(&collab), 1000);
+}
+
+#[test]
+fn test_underfunded_payer_leaves_payout_unsettled() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, AutoRoyaltyDistribution);
+    let client = AutoRoyaltyDistributionClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    let (payer, xlm) = setup_native(&env, &client);
+
+    let track_id = String::from_str(&env, "track_underfunded");
+    let collab1 = Address::generate(&env);
+    let mut collabs = Vec::new(&env);
+    collabs.push_back(Collaborator {
+        address: collab1.clone(),
+        percentage: 10000,
+    });
+    client.set_splits(&owner, &track_id, &collabs);
+
+    let payout_id = String::from_str(&env, "payout_big");
+    let result = client.try_receive_and_distribute(&payer, &track_id, &payout_id, &2_000_000, &Asset::Native);
+    assert_eq!(result, Err(Ok(Error::InsufficientBalance)));
+    assert_eq!(client.get_settlement_count(&track_id), 0);
+
+    // Once the payer is funded the same payout id goes through
+    token::StellarAssetClient::new(&env, &xlm).mint(&payer, &1_000_000);
+    client.receive_and_distribute(&payer, &track_id, &payout_id, &2_000_000, &Asset::Native);
+    assert_eq!(token::Client::new(&env, &xlm).balance(&collab1), 2_000_000);
+    assert_eq!(client.get_settlement_count(&track_id), 1);
+}
+
+#[test]
+fn test_rejected_transfers_in_best_effort_batch() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, AutoRoyaltyDistribution);
+    let client = AutoRoyaltyDistributionClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    let payer = Address::generate(&env);
+    let sac = env.register_stellar_asset_contract_v2(Address::generate(&env));
+    sac.issuer().set_flag(IssuerFlags::RevocableFlag);
+    let usdc = sac.address();
+    token::StellarAssetClient::new(&env, &usdc).mint(&payer, &1_000_000);
+
+    let track_id = String::from_str(&env, "track_frozen");
+    let open = Address::generate(&env);
+    let frozen = Address::generate(&env);
+    let mut collabs = Vec::new(&env);
+    collabs.push_back(Collaborator {
+        address: open.clone(),
+        percentage: 5000,
+    });
+    collabs.push_back(Collaborator {
+        address: frozen.clone(),
+        percentage: 5000,
+    });
+    client.set_splits(&owner, &track_id, &collabs);
+    token::StellarAssetClient::new(&env, &usdc).set_authorized(&frozen, &false);
+
+    let mut batch = Vec::new(&env);
+    batch.push_back((track_id.clone(), String::from_str(&env, "payout_frozen"), 1000_i128, Asset::Token(usdc.clone())));
+
+    // On a push track a refused share fails the payout, and nothing moves
+    let usdc_client = token::Client::new(&env, &usdc);
+    let result = client.try_batch_distribute(&payer, &batch, &BatchMode::BestEffort);
+    assert_eq!(result, Err(Ok(Error::TransferFailed)));
+    assert_eq!(client.get_settlement_count(&track_id), 0);
+    assert_eq!(usdc_client.balance(&payer), 1_000_000);
+    assert_eq!(usdc_client.balance(&open), 0);
+
+    // A track that opts in holds the refused share for the frozen collaborator
+    client.set_payout_mode(&owner, &track_id, &PayoutMode::PushOrHold);
+    let results = client.batch_distribute(&payer, &batch, &BatchMode::BestEffort);
+    assert_eq!(results, Vec::from_array(&env, [0]));
+    assert_eq!(usdc_client.balance(&open), 500);
+    assert_eq!(usdc_client.balance(&frozen), 0);
+    assert_eq!(usdc_client.balance(&contract_id), 500);
+    assert_eq!(client.get_claimable(&frozen, &Asset::Token(usdc.clone())), 500);
+
+    let record = client.get_settlement_history(&track_id, &0).unwrap();
+    assert_eq!(record.held, Vec::from_array(&env, [(frozen.clone(), 500_i128)]));
+    assert!(client.get_collaborator_payouts(&frozen, &0, &10).payouts.get(0).unwrap().held);
+    assert!(!client.get_collaborator_payouts(&open, &0, &10).payouts.get(0).unwrap().held);
+
+    // A payer the token refuses to debit is reported per item, with nothing moved
+    token::StellarAssetClient::new(&env, &usdc).set_authorized(&payer, &false);
+    let mut batch = Vec::new(&env);
+    batch.push_back((track_id.clone(), String::from_str(&env, "payout_payer_frozen"), 1000_i128, Asset::Token(usdc.clone())));
+    let results = client.batch_distribute(&payer, &batch, &BatchMode::BestEffort);
+    assert_eq!(results, Vec::from_array(&env, [Error::TransferFailed as u32]));
+    assert_eq!(client.get_settlement_count(&track_id), 1);
+    assert_eq!(usdc_client.balance(&payer), 1_000_000 - 1000);
+    assert_eq!(usdc_client.balance(&open), 500);
+}
+
+#[test]
+fn test_residual_recipient_receives_unallocated_share() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, AutoRoyaltyDistribution);
+    let client = AutoRoyaltyDistributionClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    let (payer, xlm) = setup_native(&env, &client);
+
+    let track_id = String::from_str(&env, "track_partial");
+    let collab1 = Address::generate(&env);
+    let collab2 = Address::generate(&env);
+    let mut collabs = Vec::new(&env);
+    collabs.push_back(Collaborator {
+        address: collab1.clone(),
+        percentage: 5000,
+    });
+    collabs.push_back(Collaborator {
+        address: collab2.clone(),
+        percentage: 3000,
+    });
+    client.set_splits(&owner, &track_id, &collabs);
+    assert_eq!(client.get_residual_recipient(&track_id), owner);
+
+    let label = Address::generate(&env);
+    client.set_residual_recipient(&owner, &track_id, &label);
+    assert_eq!(client.get_residual_recipient(&track_id), label);
+
+    let record = client.receive_and_distribute(&payer, &track_id, &String::from_str(&env, "payout_partial"), &1001, &Asset::Native);
+    assert_eq!(record.distributions.get(0).unwrap(), (collab1.clone(), 500));
+    // The last collaborator is no longer topped up with the remainder
+    assert_eq!(record.distributions.get(1).unwrap(), (collab2.clone(), 300));
+    assert_eq!(record.residual_recipient, label);
+    assert_eq!(record.residual_amount, 201);
+
+    let xlm_client = token::Client::new(&env, &xlm);
+    assert_eq!(xlm_client.balance(&collab2), 300);
+    assert_eq!(xlm_client.balance(&label), 201);
+    assert_eq!(xlm_client.balance(&owner), 0);
+
+    let logged = client.get_settlement_history(&track_id, &0).unwrap();
+    assert_eq!(logged.residual_amount, 201);
+}
+
+#[test]
+fn test_only_owner_manages_track() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, AutoRoyaltyDistribution);
+    let client = AutoRoyaltyDistributionClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    let intruder = Address::generate(&env);
+
+    let track_id = String::from_str(&env, "track_owned");
+    let mut collabs = Vec::new(&env);
+    collabs.push_back(Collaborator {
+        address: Address::generate(&env),
+        percentage: 10000,
+    });
+    client.set_splits(&owner, &track_id, &collabs);
+    assert_eq!(client.get_track_owner(&track_id), owner);
+
+    let result = client.try_set_splits(&intruder, &track_id, &collabs);
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+
+    let result = client.try_set_residual_recipient(&intruder, &track_id, &intruder);
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+}
+
+#[test]
+fn test_admin_assigns_owner_to_legacy_track() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, AutoRoyaltyDistribution);
+    let client = AutoRoyaltyDistributionClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    let intruder = Address::generate(&env);
+    setup_native(&env, &client);
+
+    // A track configured before splits recorded an owner
+    let track_id = String::from_str(&env, "track_legacy");
+    let mut collabs = Vec::new(&env);
+    collabs.push_back(Collaborator {
+        address: Address::generate(&env),
+        percentage: 10000,
+    });
+    env.as_contract(&contract_id, || {
+        env.storage()
+            .persistent()
+            .set(&DataKey::TrackSplits(track_id.clone()), &collabs);
+    });
+
+    // Nobody can take it over by being the first to call set_splits
+    let result = client.try_set_splits(&intruder, &track_id, &collabs);
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+    assert_eq!(client.try_get_track_owner(&track_id), Err(Ok(Error::TrackNotFound)));
+
+    client.assign_track_owner(&track_id, &owner);
+    assert_eq!(client.get_track_owner(&track_id), owner);
+    assert_eq!(client.get_residual_recipient(&track_id), owner);
+    client.set_splits(&owner, &track_id, &collabs);
+
+    let result = client.try_assign_track_owner(&track_id, &intruder);
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+    let result = client.try_assign_track_owner(&String::from_str(&env, "track_missing"), &owner);
+    assert_eq!(result, Err(Ok(Error::TrackNotFound)));
+}
+
+#[test]
+fn test_pull_mode_credits_claimable_balances() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, AutoRoyaltyDistribution);
+    let client = AutoRoyaltyDistributionClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    let (payer, xlm) = setup_native(&env, &client);
+
+    let track_id = String::from_str(&env, "track_pull");
+    let collab1 = Address::generate(&env);
+    let collab2 = Address::generate(&env);
+    let mut collabs = Vec::new(&env);
+    collabs.push_back(Collaborator {
+        address: collab1.clone(),
+        percentage: 6000,
+    });
+    collabs.push_back(Collaborator {
+        address: collab2.clone(),
+        percentage: 3000,
+    });
+    client.set_splits(&owner, &track_id, &collabs);
+    assert_eq!(client.get_payout_mode(&track_id), PayoutMode::Push);
+    client.set_payout_mode(&owner, &track_id, &PayoutMode::Pull);
+    assert_eq!(client.get_payout_mode(&track_id), PayoutMode::Pull);
+
+    client.receive_and_distribute(&payer, &track_id, &String::from_str(&env, "pull_1"), &1000, &Asset::Native);
+    client.receive_and_distribute(&payer, &track_id, &String::from_str(&env, "pull_2"), &500, &Asset::Native);
+
+    let xlm_client = token::Client::new(&env, &xlm);
+    assert_eq!(xlm_client.balance(&contract_id), 1500);
+    assert_eq!(xlm_client.balance(&collab1), 0);
+    assert_eq!(client.get_claimable(&collab1, &Asset::Native), 900);
+    assert_eq!(client.get_claimable(&collab2, &Asset::Native), 450);
+    assert_eq!(client.get_claimable(&owner, &Asset::Native), 150);
+    // Balances are kept per token contract, so native credits show under XLM's address too
+    assert_eq!(client.get_claimable(&collab1, &Asset::Token(xlm.clone())), 900);
+
+    assert_eq!(client.claim(&collab1, &Asset::Native), 900);
+    assert_eq!(xlm_client.balance(&collab1), 900);
+    assert_eq!(xlm_client.balance(&contract_id), 600);
+    assert_eq!(client.get_claimable(&collab1, &Asset::Native), 0);
+
+    let result = client.try_claim(&collab1, &Asset::Native);
+    assert_eq!(result, Err(Ok(Error::NothingToClaim)));
+}
+
+#[test]
+fn test_only_owner_sets_payout_mode() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, AutoRoyaltyDistribution);
+    let client = AutoRoyaltyDistributionClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+
+    let track_id = String::from_str(&env, "track_pull_owner");
+    let mut collabs = Vec::new(&env);
+    collabs.push_back(Collaborator {
+        address: Address::generate(&env),
+        percentage: 10000,
+    });
+    client.set_splits(&owner, &track_id, &collabs);
+
+    let result = client.try_set_payout_mode(&Address::generate(&env), &track_id, &PayoutMode::Pull);
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+}
+
+/// Settle five payouts of 100, 200, ... 500 on one track, one every 10 seconds
+/// starting at timestamp 1000.
+fn setup_history(env: &Env, client: &AutoRoyaltyDistributionClient) -> (String, Address, Address) {
+    let owner = Address::generate(env);
+    let (payer, _) = setup_native(env, client);
+    let collab = Address::generate(env);
+    let mut collabs = Vec::new(env);
+    collabs.push_back(Collaborator {
+        address: collab.clone(),
+        percentage: 9000,
+    });
+    let track_id = String::from_str(env, "track_history");
+    client.set_splits(&owner, &track_id, &collabs);
+
+    let payout_ids = ["h_0", "h_1", "h_2", "h_3", "h_4"];
+    for (i, payout_id) in payout_ids.iter().enumerate() {
+        env.ledger().with_mut(|l| l.timestamp = 1000 + 10 * i as u64);
+        let amount = 100 * (i as i128 + 1);
+        client.receive_and_distribute(&payer, &track_id, &String::from_str(env, payout_id), &amount, &Asset::Native);
+    }
+    (track_id, owner, collab)
+}
+
+#[test]
+fn test_settlement_pages() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, AutoRoyaltyDistribution);
+    let client = AutoRoyaltyDistributionClient::new(&env, &contract_id);
+    let (track_id, _, collab) = setup_history(&env, &client);
+
+    let page = client.get_settlements(&track_id, &0, &2);
+    assert_eq!(page.records.len(), 2);
+    assert_eq!(page.records.get(0).unwrap().total_amount, 100);
+    assert_eq!(page.next_cursor, Some(2));
+
+    let page = client.get_settlements(&track_id, &4, &10);
+    assert_eq!(page.records.len(), 1);
+    assert_eq!(page.records.get(0).unwrap().total_amount, 500);
+    assert_eq!(page.next_cursor, None);
+
+    // Zero limits return an empty page without a cursor to follow
+    assert_eq!(client.get_settlements(&track_id, &0, &0).next_cursor, None);
+    assert_eq!(client.get_settlements_by_time(&track_id, &0, &5000, &0, &0).next_cursor, None);
+    assert_eq!(client.get_collaborator_payouts(&collab, &0, &0).next_cursor, None);
+}
+
+#[test]
+fn test_settlements_by_time() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, AutoRoyaltyDistribution);
+    let client = AutoRoyaltyDistributionClient::new(&env, &contract_id);
+    let (track_id, _, _) = setup_history(&env, &client);
+
+    // Timestamps 1010, 1020 and 1030 fall in range
+    let page = client.get_settlements_by_time(&track_id, &1005, &1030, &0, &2);
+    assert_eq!(page.records.len(), 2);
+    assert_eq!(page.records.get(0).unwrap().timestamp, 1010);
+    assert_eq!(page.next_cursor, Some(3));
+
+    let page = client.get_settlements_by_time(&track_id, &1005, &1030, &3, &2);
+    assert_eq!(page.records.len(), 1);
+    assert_eq!(page.records.get(0).unwrap().timestamp, 1030);
+    assert_eq!(page.next_cursor, None);
+
+    let page = client.get_settlements_by_time(&track_id, &2000, &3000, &0, &10);
+    assert_eq!(page.records.len(), 0);
+    assert_eq!(page.next_cursor, None);
+}
+
+#[test]
+fn test_collaborator_payouts_and_totals() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, AutoRoyaltyDistribution);
+    let client = AutoRoyaltyDistributionClient::new(&env, &contract_id);
+    let (track_id, owner, collab) = setup_history(&env, &client);
+
+    let page = client.get_collaborator_payouts(&collab, &0, &3);
+    assert_eq!(page.payouts.len(), 3);
+    assert_eq!(page.next_cursor, Some(3));
+    let first = page.payouts.get(0).unwrap();
+    assert_eq!(first.track_id, track_id);
+    assert_eq!(first.log_index, 0);
+    assert_eq!(first.amount, 90);
+    assert!(!first.residual);
+
+    let page = client.get_collaborator_payouts(&collab, &3, &3);
+    assert_eq!(page.payouts.len(), 2);
+    assert_eq!(page.payouts.get(1).unwrap().amount, 450);
+    assert_eq!(page.next_cursor, None);
+
+    // The owner collects the unallocated 10% as residual recipient
+    let owner_page = client.get_collaborator_payouts(&owner, &0, &10);
+    assert_eq!(owner_page.payouts.len(), 5);
+    assert!(owner_page.payouts.get(0).unwrap().residual);
+
+    assert_eq!(client.get_track_total(&track_id, &Asset::Native), 1500);
+    assert_eq!(client.get_collaborator_total(&collab, &Asset::Native), 1350);
+    assert_eq!(client.get_collaborator_total(&owner, &Asset::Native), 150);
+    assert_eq!(client.get_track_total(&track_id, &Asset::Token(collab.clone())), 0);
+}
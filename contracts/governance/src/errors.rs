@@ -19,4 +19,7 @@ pub enum Error {
     InvalidDelegatee      = 13,
     SelfDelegation        = 14,
     QuorumNotReached      = 15,
+    InvalidAction         = 16,
+    AlreadyCancelled      = 17,
+    InvalidAmount         = 18,
 }
\ No newline at end of file
@@ -8,7 +8,7 @@ pub fn proposal_created(
 ) {
     env.events().publish(
         (symbol_short!("GOV"), symbol_short!("PROPOSE")),
-        (proposal_id, proposer, voting_ends_at),
+        (proposal_id.clone(), proposer.clone(), voting_ends_at),
     );
 }
 
@@ -21,7 +21,14 @@ pub fn vote_cast(
 ) {
     env.events().publish(
         (symbol_short!("GOV"), symbol_short!("VOTE")),
-        (proposal_id, voter, support, voting_power),
+        (proposal_id.clone(), voter.clone(), support, voting_power),
+    );
+}
+
+pub fn proposal_finalized(env: &Env, proposal_id: &String, passed: bool) {
+    env.events().publish(
+        (symbol_short!("GOV"), symbol_short!("FINAL")),
+        (proposal_id.clone(), passed),
     );
 }
 
@@ -32,7 +39,7 @@ pub fn proposal_executed(
 ) {
     env.events().publish(
         (symbol_short!("GOV"), symbol_short!("EXEC")),
-        (proposal_id, executor),
+        (proposal_id.clone(), executor.clone()),
     );
 }
 
@@ -43,13 +50,55 @@ pub fn delegation_set(
 ) {
     env.events().publish(
         (symbol_short!("GOV"), symbol_short!("DELEG")),
-        (delegator, delegatee),
+        (delegator.clone(), delegatee.clone()),
+    );
+}
+
+pub fn delegation_removed(env: &Env, delegator: &Address) {
+    env.events().publish(
+        (symbol_short!("GOV"), symbol_short!("UNDELEG")),
+        (delegator.clone(),),
+    );
+}
+
+pub fn votes_locked(env: &Env, account: &Address, amount: i128) {
+    env.events().publish(
+        (symbol_short!("GOV"), symbol_short!("LOCK")),
+        (account.clone(), amount),
+    );
+}
+
+pub fn votes_unlocked(env: &Env, account: &Address, amount: i128) {
+    env.events().publish(
+        (symbol_short!("GOV"), symbol_short!("UNLOCK")),
+        (account.clone(), amount),
+    );
+}
+
+pub fn action_registered(env: &Env, contract: &Address, function: &String, arity: u32) {
+    env.events().publish(
+        (symbol_short!("GOV"), symbol_short!("ACTION")),
+        (contract.clone(), function.clone(), arity),
+    );
+}
+
+pub fn action_unregistered(env: &Env, contract: &Address, function: &String) {
+    env.events().publish(
+        (symbol_short!("GOV"), symbol_short!("UNACTION")),
+        (contract.clone(), function.clone()),
+    );
+}
+
+pub fn admin_changed(env: &Env, new_admin: &Address) {
+    env.events().publish(
+        (symbol_short!("GOV"), symbol_short!("ADMIN")),
+        (new_admin.clone(),),
     );
 }
 
 pub fn proposal_cancelled(env: &Env, proposal_id: &String) {
     env.events().publish(
         (symbol_short!("GOV"), symbol_short!("CANCEL")),
-        (proposal_id,),
+        (proposal_id.clone(),),
     );
 }
\ No newline at end of file
@@ -1,14 +1,521 @@
-pub fn add(left: u64, right: u64) -> u64 {
-    left + right
+#![no_std]
+
+pub mod errors;
+pub mod event;
+pub mod storage;
+pub mod types;
+pub mod voting;
+
+use soroban_sdk::{contract, contractimpl, token, Address, Env, String, Symbol, TryFromVal, Val, Vec};
+
+pub use errors::Error;
+pub use types::{
+    Checkpoint, DelegationRecord, GovernanceConfig, Proposal, ProposalAction, ProposalStatus,
+    VoteRecord,
+};
+
+const BASIS_POINTS: i128 = 10_000;
+const MAX_ACTIONS: u32 = 10;
+const MAX_ACTION_ARGS: u32 = 10;
+const MAX_FUNCTION_NAME_LEN: usize = 32;
+/// Admin functions of this contract that proposals may call, with their arity.
+const SELF_ACTIONS: [(&str, u32); 3] = [("register_action", 3), ("unregister_action", 2), ("set_admin", 1)];
+
+#[contract]
+pub struct GovernanceContract;
+
+#[contractimpl]
+impl GovernanceContract {
+    pub fn initialize(
+        env: Env,
+        admin: Address,
+        token: Address,
+        voting_period_ledgers: u32,
+        timelock_ledgers: u32,
+        quorum_basis_points: u32,
+        proposal_threshold: i128,
+    ) -> Result<(), Error> {
+        if storage::get_config(&env).is_some() {
+            return Err(Error::AlreadyInitialized);
+        }
+        admin.require_auth();
+
+        let config = GovernanceConfig {
+            admin,
+            token,
+            voting_period_ledgers,
+            timelock_ledgers,
+            quorum_basis_points: quorum_basis_points.min(BASIS_POINTS as u32),
+            proposal_threshold,
+        };
+        storage::set_config(&env, &config);
+        Ok(())
+    }
+
+    /// Allow proposals to call `function` on `contract` with exactly `arity`
+    /// arguments. Registering again replaces the arity.
+    ///
+    /// The registry is trusted to the admin: it decides which calls new
+    /// proposals may carry, and can cancel proposals. It only gates proposal
+    /// creation, though. A proposal keeps the arguments it was created with
+    /// and `execute` never consults the registry, so later changes cannot
+    /// block a proposal that already passed. Proposals can also manage the
+    /// registry and the admin themselves by targeting this contract's
+    /// `register_action`, `unregister_action` and `set_admin`; handing the
+    /// admin to this contract's address leaves those changes to proposals
+    /// alone.
+    pub fn register_action(
+        env: Env,
+        contract: Address,
+        function: String,
+        arity: u32,
+    ) -> Result<(), Error> {
+        let config = storage::get_config(&env).ok_or(Error::NotInitialized)?;
+        config.admin.require_auth();
+
+        register(&env, &contract, &function, arity)
+    }
+
+    /// Stop new proposals from calling `function` on `contract`. Admin only;
+    /// proposals already created can still execute it.
+    pub fn unregister_action(env: Env, contract: Address, function: String) -> Result<(), Error> {
+        let config = storage::get_config(&env).ok_or(Error::NotInitialized)?;
+        config.admin.require_auth();
+
+        unregister(&env, &contract, &function)
+    }
+
+    /// Hand the admin role to `new_admin`. Admin only. Passing this
+    /// contract's own address puts the registry under proposal control.
+    pub fn set_admin(env: Env, new_admin: Address) -> Result<(), Error> {
+        let config = storage::get_config(&env).ok_or(Error::NotInitialized)?;
+        config.admin.require_auth();
+
+        replace_admin(&env, config, new_admin);
+        Ok(())
+    }
+
+    /// Lock governance tokens in the contract to gain voting power. Power
+    /// follows the account's delegation and counts for proposals created
+    /// from the next ledger onwards.
+    pub fn deposit(env: Env, account: Address, amount: i128) -> Result<(), Error> {
+        account.require_auth();
+        let config = storage::get_config(&env).ok_or(Error::NotInitialized)?;
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        token::Client::new(&env, &config.token).transfer(
+            &account,
+            &env.current_contract_address(),
+            &amount,
+        );
+        storage::set_locked(&env, &account, storage::get_locked(&env, &account) + amount);
+        voting::move_flow(&env, &account, amount);
+        voting::add_total_votes(&env, amount);
+
+        event::votes_locked(&env, &account, amount);
+        Ok(())
+    }
+
+    /// Unlock governance tokens. Votes already cast are unaffected because
+    /// they were weighed at each proposal's snapshot.
+    pub fn withdraw(env: Env, account: Address, amount: i128) -> Result<(), Error> {
+        account.require_auth();
+        let config = storage::get_config(&env).ok_or(Error::NotInitialized)?;
+        let locked = storage::get_locked(&env, &account);
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        if amount > locked {
+            return Err(Error::InsufficientVotingPower);
+        }
+
+        storage::set_locked(&env, &account, locked - amount);
+        voting::move_flow(&env, &account, -amount);
+        voting::add_total_votes(&env, -amount);
+        token::Client::new(&env, &config.token).transfer(
+            &env.current_contract_address(),
+            &account,
+            &amount,
+        );
+
+        event::votes_unlocked(&env, &account, amount);
+        Ok(())
+    }
+
+    /// Delegate the delegator's voting power, including anything delegated to
+    /// it, to `delegatee`. Delegation is transitive: if the delegatee has
+    /// itself delegated, the power ends up at the end of the chain. Proposals
+    /// that already exist keep the power distribution of their snapshot.
+    pub fn delegate(env: Env, delegator: Address, delegatee: Address) -> Result<(), Error> {
+        delegator.require_auth();
+        storage::get_config(&env).ok_or(Error::NotInitialized)?;
+        if delegator == delegatee {
+            return Err(Error::SelfDelegation);
+        }
+        if let Some(record) = storage::get_delegation(&env, &delegator) {
+            if record.delegatee == delegatee {
+                return Ok(());
+            }
+        }
+        voting::check_delegation_chain(&env, &delegator, &delegatee)?;
+
+        voting::detach(&env, &delegator);
+        let record = DelegationRecord {
+            delegator: delegator.clone(),
+            delegatee: delegatee.clone(),
+            delegated_at: env.ledger().sequence() as u64,
+        };
+        storage::set_delegation(&env, &delegator, &record);
+        voting::attach(&env, &delegator);
+
+        event::delegation_set(&env, &delegator, &delegatee);
+        Ok(())
+    }
+
+    /// Take back voting power previously delegated.
+    pub fn undelegate(env: Env, delegator: Address) -> Result<(), Error> {
+        delegator.require_auth();
+        storage::get_config(&env).ok_or(Error::NotInitialized)?;
+        if storage::get_delegation(&env, &delegator).is_none() {
+            return Err(Error::InvalidDelegatee);
+        }
+
+        voting::detach(&env, &delegator);
+        storage::remove_delegation(&env, &delegator);
+        voting::attach(&env, &delegator);
+
+        event::delegation_removed(&env, &delegator);
+        Ok(())
+    }
+
+    /// Open a proposal for voting. Voting power is snapshotted at the end of
+    /// the previous ledger, and the proposer must have held at least
+    /// `proposal_threshold` of it at that point. Every action must call a
+    /// function registered through `register_action`, or one of this
+    /// contract's own admin functions, with its exact number of arguments.
+    /// The arguments are stored with the proposal and are what `execute`
+    /// passes, whatever happens to the registry afterwards.
+    pub fn create_proposal(
+        env: Env,
+        proposer: Address,
+        description: String,
+        actions: Vec<ProposalAction>,
+    ) -> Result<String, Error> {
+        proposer.require_auth();
+        let config = storage::get_config(&env).ok_or(Error::NotInitialized)?;
+
+        if description.is_empty() {
+            return Err(Error::InvalidDescription);
+        }
+        if actions.len() > MAX_ACTIONS {
+            return Err(Error::InvalidAction);
+        }
+        for action in actions.iter() {
+            function_symbol(&env, &action.function)?;
+            if action_arity(&env, &action.contract, &action.function) != Some(action.args.len()) {
+                return Err(Error::InvalidAction);
+            }
+        }
+
+        let snapshot_ledger = env.ledger().sequence().saturating_sub(1);
+        let proposer_votes = voting::votes_at(&env, &proposer, snapshot_ledger);
+        if proposer_votes < config.proposal_threshold {
+            return Err(Error::InsufficientVotingPower);
+        }
+
+        let count = storage::increment_proposal_count(&env);
+        let proposal_id = proposal_id_from_count(&env, count);
+        let created_at = env.ledger().sequence() as u64;
+        let voting_ends_at = created_at + config.voting_period_ledgers as u64;
+
+        let proposal = Proposal {
+            proposal_id: proposal_id.clone(),
+            proposer: proposer.clone(),
+            description,
+            actions,
+            votes_for: 0,
+            votes_against: 0,
+            created_at,
+            voting_ends_at,
+            execution_available_at: voting_ends_at + config.timelock_ledgers as u64,
+            status: ProposalStatus::Active,
+            snapshot_ledger,
+        };
+        storage::set_proposal(&env, &proposal_id, &proposal);
+
+        event::proposal_created(&env, &proposal_id, &proposer, voting_ends_at);
+        Ok(proposal_id)
+    }
+
+    /// Vote on an active proposal with the voting power the voter held at the
+    /// proposal's snapshot.
+    pub fn cast_vote(
+        env: Env,
+        voter: Address,
+        proposal_id: String,
+        support: bool,
+    ) -> Result<i128, Error> {
+        voter.require_auth();
+        let mut proposal =
+            storage::get_proposal(&env, &proposal_id).ok_or(Error::ProposalNotFound)?;
+
+        if proposal.status != ProposalStatus::Active
+            || env.ledger().sequence() as u64 > proposal.voting_ends_at
+        {
+            return Err(Error::VotingClosed);
+        }
+        if storage::get_vote(&env, &proposal_id, &voter).is_some() {
+            return Err(Error::AlreadyVoted);
+        }
+
+        let voting_power = voting::votes_at(&env, &voter, proposal.snapshot_ledger);
+        if voting_power <= 0 {
+            return Err(Error::InsufficientVotingPower);
+        }
+
+        if support {
+            proposal.votes_for += voting_power;
+        } else {
+            proposal.votes_against += voting_power;
+        }
+        storage::set_proposal(&env, &proposal_id, &proposal);
+
+        let record = VoteRecord {
+            voter: voter.clone(),
+            proposal_id: proposal_id.clone(),
+            support,
+            voting_power,
+            voted_at: env.ledger().timestamp(),
+        };
+        storage::set_vote(&env, &proposal_id, &voter, &record);
+
+        event::vote_cast(&env, &proposal_id, &voter, support, voting_power);
+        Ok(voting_power)
+    }
+
+    /// Close voting once the period has ended. The proposal passes if turnout
+    /// reaches `quorum_basis_points` of the voting power locked at the snapshot
+    /// and more votes were cast for than against; otherwise it is rejected.
+    /// Callable by anyone.
+    pub fn finalize(env: Env, proposal_id: String) -> Result<ProposalStatus, Error> {
+        let config = storage::get_config(&env).ok_or(Error::NotInitialized)?;
+        let mut proposal =
+            storage::get_proposal(&env, &proposal_id).ok_or(Error::ProposalNotFound)?;
+
+        if proposal.status != ProposalStatus::Active {
+            return Err(Error::VotingClosed);
+        }
+        let current_ledger = env.ledger().sequence() as u64;
+        if current_ledger <= proposal.voting_ends_at {
+            return Err(Error::VotingStillOpen);
+        }
+
+        let total_votes = voting::total_votes_at(&env, proposal.snapshot_ledger);
+        let quorum = total_votes * config.quorum_basis_points as i128 / BASIS_POINTS;
+        let turnout = proposal.votes_for + proposal.votes_against;
+
+        proposal.status = if turnout >= quorum && proposal.votes_for > proposal.votes_against {
+            proposal.execution_available_at = current_ledger + config.timelock_ledgers as u64;
+            ProposalStatus::Passed
+        } else {
+            ProposalStatus::Rejected
+        };
+        storage::set_proposal(&env, &proposal_id, &proposal);
+
+        event::proposal_finalized(&env, &proposal_id, proposal.status == ProposalStatus::Passed);
+        Ok(proposal.status)
+    }
+
+    /// Run a passed proposal's actions once its timelock has elapsed. Each
+    /// action is invoked with its arguments and the governance contract as
+    /// the caller; if any action fails the whole execution reverts.
+    pub fn execute(env: Env, executor: Address, proposal_id: String) -> Result<(), Error> {
+        executor.require_auth();
+        let mut proposal =
+            storage::get_proposal(&env, &proposal_id).ok_or(Error::ProposalNotFound)?;
+
+        match proposal.status {
+            ProposalStatus::Passed => {}
+            ProposalStatus::Executed => return Err(Error::AlreadyExecuted),
+            _ => return Err(Error::ProposalNotPassed),
+        }
+        if (env.ledger().sequence() as u64) < proposal.execution_available_at {
+            return Err(Error::TimelockNotExpired);
+        }
+
+        // Mark executed before calling out so a re-entrant call cannot run it twice.
+        proposal.status = ProposalStatus::Executed;
+        storage::set_proposal(&env, &proposal_id, &proposal);
+
+        for action in proposal.actions.iter() {
+            if action.contract == env.current_contract_address() {
+                apply_self_action(&env, &action.function, &action.args)?;
+                continue;
+            }
+            let function = function_symbol(&env, &action.function)?;
+            env.invoke_contract::<Val>(&action.contract, &function, action.args);
+        }
+
+        event::proposal_executed(&env, &proposal_id, &executor);
+        Ok(())
+    }
+
+    /// Cancel a proposal that has not been executed. Callable by the proposer
+    /// or the admin.
+    pub fn cancel(env: Env, caller: Address, proposal_id: String) -> Result<(), Error> {
+        caller.require_auth();
+        let config = storage::get_config(&env).ok_or(Error::NotInitialized)?;
+        let mut proposal =
+            storage::get_proposal(&env, &proposal_id).ok_or(Error::ProposalNotFound)?;
+
+        if caller != proposal.proposer && caller != config.admin {
+            return Err(Error::Unauthorized);
+        }
+        match proposal.status {
+            ProposalStatus::Executed => return Err(Error::AlreadyExecuted),
+            ProposalStatus::Cancelled => return Err(Error::AlreadyCancelled),
+            _ => {}
+        }
+
+        proposal.status = ProposalStatus::Cancelled;
+        storage::set_proposal(&env, &proposal_id, &proposal);
+
+        event::proposal_cancelled(&env, &proposal_id);
+        Ok(())
+    }
+
+    pub fn get_config(env: Env) -> Result<GovernanceConfig, Error> {
+        storage::get_config(&env).ok_or(Error::NotInitialized)
+    }
+
+    pub fn get_proposal(env: Env, proposal_id: String) -> Result<Proposal, Error> {
+        storage::get_proposal(&env, &proposal_id).ok_or(Error::ProposalNotFound)
+    }
+
+    pub fn get_vote(env: Env, proposal_id: String, voter: Address) -> Option<VoteRecord> {
+        storage::get_vote(&env, &proposal_id, &voter)
+    }
+
+    pub fn get_locked(env: Env, account: Address) -> i128 {
+        storage::get_locked(&env, &account)
+    }
+
+    pub fn get_delegation(env: Env, delegator: Address) -> Option<DelegationRecord> {
+        storage::get_delegation(&env, &delegator)
+    }
+
+    /// Registered argument count for a proposal action, if any.
+    pub fn get_action_arity(env: Env, contract: Address, function: String) -> Option<u32> {
+        storage::get_action_arity(&env, &contract, &function)
+    }
+
+    /// Voting power the account can currently cast.
+    pub fn get_votes(env: Env, account: Address) -> i128 {
+        voting::current_votes(&env, &account)
+    }
+
+    /// Voting power the account could cast as of the end of `ledger`.
+    pub fn get_votes_at(env: Env, account: Address, ledger: u32) -> i128 {
+        voting::votes_at(&env, &account, ledger)
+    }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+fn register(env: &Env, contract: &Address, function: &String, arity: u32) -> Result<(), Error> {
+    function_symbol(env, function)?;
+    if arity > MAX_ACTION_ARGS {
+        return Err(Error::InvalidAction);
+    }
+    storage::set_action_arity(env, contract, function, arity);
+
+    event::action_registered(env, contract, function, arity);
+    Ok(())
+}
+
+fn unregister(env: &Env, contract: &Address, function: &String) -> Result<(), Error> {
+    if storage::get_action_arity(env, contract, function).is_none() {
+        return Err(Error::InvalidAction);
+    }
+    storage::remove_action_arity(env, contract, function);
+
+    event::action_unregistered(env, contract, function);
+    Ok(())
+}
+
+fn replace_admin(env: &Env, mut config: GovernanceConfig, new_admin: Address) {
+    config.admin = new_admin.clone();
+    storage::set_config(env, &config);
+
+    event::admin_changed(env, &new_admin);
+}
+
+/// Number of arguments a proposal must pass to `function` on `contract`.
+/// Besides registered actions, proposals may call this contract's own
+/// `SELF_ACTIONS`.
+fn action_arity(env: &Env, contract: &Address, function: &String) -> Option<u32> {
+    if *contract != env.current_contract_address() {
+        return storage::get_action_arity(env, contract, function);
+    }
+    SELF_ACTIONS
+        .iter()
+        .find(|(name, _)| *function == String::from_str(env, name))
+        .map(|(_, arity)| *arity)
+}
 
-    #[test]
-    fn it_works() {
-        let result = add(2, 2);
-        assert_eq!(result, 4);
+/// Run one of this contract's admin functions on behalf of a proposal. A
+/// contract cannot invoke itself, so `execute` applies these directly.
+fn apply_self_action(env: &Env, function: &String, args: &Vec<Val>) -> Result<(), Error> {
+    let config = storage::get_config(env).ok_or(Error::NotInitialized)?;
+    if *function == String::from_str(env, "register_action") {
+        register(env, &arg(env, args, 0)?, &arg(env, args, 1)?, arg(env, args, 2)?)
+    } else if *function == String::from_str(env, "unregister_action") {
+        unregister(env, &arg(env, args, 0)?, &arg(env, args, 1)?)
+    } else if *function == String::from_str(env, "set_admin") {
+        replace_admin(env, config, arg(env, args, 0)?);
+        Ok(())
+    } else {
+        Err(Error::InvalidAction)
     }
 }
+
+/// Decode argument `index` of a proposal action.
+fn arg<T: TryFromVal<Env, Val>>(env: &Env, args: &Vec<Val>, index: u32) -> Result<T, Error> {
+    let value = args.get(index).ok_or(Error::InvalidAction)?;
+    T::try_from_val(env, &value).map_err(|_| Error::InvalidAction)
+}
+
+/// Convert an action's function name into a `Symbol`, rejecting names that
+/// are not valid contract function identifiers.
+fn function_symbol(env: &Env, function: &String) -> Result<Symbol, Error> {
+    let len = function.len() as usize;
+    if len == 0 || len > MAX_FUNCTION_NAME_LEN {
+        return Err(Error::InvalidAction);
+    }
+    let mut buffer = [0u8; MAX_FUNCTION_NAME_LEN];
+    function.copy_into_slice(&mut buffer[..len]);
+    if !buffer[..len].iter().all(|c| c.is_ascii_alphanumeric() || *c == b'_') {
+        return Err(Error::InvalidAction);
+    }
+    let name = core::str::from_utf8(&buffer[..len]).map_err(|_| Error::InvalidAction)?;
+    Ok(Symbol::new(env, name))
+}
+
+fn proposal_id_from_count(env: &Env, count: u32) -> String {
+    let mut buffer = [0u8; 10];
+    let mut num = count;
+    let mut len = 0;
+    loop {
+        buffer[len] = b'0' + (num % 10) as u8;
+        num /= 10;
+        len += 1;
+        if num == 0 {
+            break;
+        }
+    }
+    buffer[..len].reverse();
+    String::from_bytes(env, &buffer[..len])
+}
+
+#[cfg(test)]
+mod test;
@@ -1,11 +1,19 @@
-use soroban_sdk::{Address, Env, String};
-use crate::types::{GovernanceConfig, Proposal, VoteRecord, DelegationRecord};
+use soroban_sdk::{Address, Env, String, Vec};
+use crate::types::{Checkpoint, GovernanceConfig, Proposal, VoteRecord, DelegationRecord};
 
 const CONFIG_KEY: &str = "CONFIG";
 const PROPOSAL_PREFIX: &str = "PROPOSAL";
 const VOTE_PREFIX: &str = "VOTE";
 const DELEGATION_PREFIX: &str = "DELEGATION";
 const PROPOSAL_COUNT_KEY: &str = "PROP_COUNT";
+const LOCKED_PREFIX: &str = "LOCKED";
+const FLOW_PREFIX: &str = "FLOW";
+const CHECKPOINT_PREFIX: &str = "CHECKPOINT";
+const CHECKPOINT_COUNT_PREFIX: &str = "CKPT_COUNT";
+const TOTAL_CHECKPOINT_PREFIX: &str = "TOTAL_CKPT";
+const TOTAL_CHECKPOINT_COUNT_KEY: &str = "TOTAL_CKPT_COUNT";
+const INBOUND_PREFIX: &str = "INBOUND";
+const ARITY_PREFIX: &str = "ARITY";
 
 pub fn get_config(env: &Env) -> Option<GovernanceConfig> {
     env.storage().instance().get(&CONFIG_KEY)
@@ -16,35 +24,40 @@ pub fn set_config(env: &Env, config: &GovernanceConfig) {
 }
 
 pub fn get_proposal(env: &Env, proposal_id: &String) -> Option<Proposal> {
-    let key = (PROPOSAL_PREFIX, proposal_id);
+    let key = (PROPOSAL_PREFIX, proposal_id.clone());
     env.storage().persistent().get(&key)
 }
 
 pub fn set_proposal(env: &Env, proposal_id: &String, proposal: &Proposal) {
-    let key = (PROPOSAL_PREFIX, proposal_id);
+    let key = (PROPOSAL_PREFIX, proposal_id.clone());
     env.storage().persistent().set(&key, proposal);
 }
 
 pub fn get_vote(env: &Env, proposal_id: &String, voter: &Address) -> Option<VoteRecord> {
-    let key = (VOTE_PREFIX, proposal_id, voter);
+    let key = (VOTE_PREFIX, proposal_id.clone(), voter.clone());
     env.storage().persistent().get(&key)
 }
 
 pub fn set_vote(env: &Env, proposal_id: &String, voter: &Address, record: &VoteRecord) {
-    let key = (VOTE_PREFIX, proposal_id, voter);
+    let key = (VOTE_PREFIX, proposal_id.clone(), voter.clone());
     env.storage().persistent().set(&key, record);
 }
 
 pub fn get_delegation(env: &Env, delegator: &Address) -> Option<DelegationRecord> {
-    let key = (DELEGATION_PREFIX, delegator);
+    let key = (DELEGATION_PREFIX, delegator.clone());
     env.storage().persistent().get(&key)
 }
 
 pub fn set_delegation(env: &Env, delegator: &Address, record: &DelegationRecord) {
-    let key = (DELEGATION_PREFIX, delegator);
+    let key = (DELEGATION_PREFIX, delegator.clone());
     env.storage().persistent().set(&key, record);
 }
 
+pub fn remove_delegation(env: &Env, delegator: &Address) {
+    let key = (DELEGATION_PREFIX, delegator.clone());
+    env.storage().persistent().remove(&key);
+}
+
 pub fn get_proposal_count(env: &Env) -> u32 {
     env.storage().instance().get(&PROPOSAL_COUNT_KEY).unwrap_or(0)
 }
@@ -53,4 +66,96 @@ pub fn increment_proposal_count(env: &Env) -> u32 {
     let count = get_proposal_count(env) + 1;
     env.storage().instance().set(&PROPOSAL_COUNT_KEY, &count);
     count
-}
\ No newline at end of file
+}
+
+/// Tokens `account` has locked in the contract for voting.
+pub fn get_locked(env: &Env, account: &Address) -> i128 {
+    let key = (LOCKED_PREFIX, account.clone());
+    env.storage().persistent().get(&key).unwrap_or(0)
+}
+
+pub fn set_locked(env: &Env, account: &Address, amount: i128) {
+    let key = (LOCKED_PREFIX, account.clone());
+    env.storage().persistent().set(&key, &amount);
+}
+
+/// Power flowing through `account`: its own locked tokens plus everything
+/// delegated to it, whether or not it has delegated onwards.
+pub fn get_flow(env: &Env, account: &Address) -> i128 {
+    let key = (FLOW_PREFIX, account.clone());
+    env.storage().persistent().get(&key).unwrap_or(0)
+}
+
+pub fn set_flow(env: &Env, account: &Address, amount: i128) {
+    let key = (FLOW_PREFIX, account.clone());
+    env.storage().persistent().set(&key, &amount);
+}
+
+/// Accounts whose delegation chains lead into `account`, counted by hop
+/// distance: entry `d` holds the number of accounts `d + 1` hops upstream.
+pub fn get_inbound(env: &Env, account: &Address) -> Vec<u32> {
+    let key = (INBOUND_PREFIX, account.clone());
+    env.storage().persistent().get(&key).unwrap_or(Vec::new(env))
+}
+
+pub fn set_inbound(env: &Env, account: &Address, inbound: &Vec<u32>) {
+    let key = (INBOUND_PREFIX, account.clone());
+    env.storage().persistent().set(&key, inbound);
+}
+
+/// Number of arguments `function` on `contract` takes, if the admin has
+/// registered it as a proposal action.
+pub fn get_action_arity(env: &Env, contract: &Address, function: &String) -> Option<u32> {
+    let key = (ARITY_PREFIX, contract.clone(), function.clone());
+    env.storage().persistent().get(&key)
+}
+
+pub fn set_action_arity(env: &Env, contract: &Address, function: &String, arity: u32) {
+    let key = (ARITY_PREFIX, contract.clone(), function.clone());
+    env.storage().persistent().set(&key, &arity);
+}
+
+pub fn remove_action_arity(env: &Env, contract: &Address, function: &String) {
+    let key = (ARITY_PREFIX, contract.clone(), function.clone());
+    env.storage().persistent().remove(&key);
+}
+
+/// Checkpoint `index` of `account`'s voting power history.
+pub fn get_checkpoint(env: &Env, account: &Address, index: u32) -> Option<Checkpoint> {
+    let key = (CHECKPOINT_PREFIX, account.clone(), index);
+    env.storage().persistent().get(&key)
+}
+
+pub fn set_checkpoint(env: &Env, account: &Address, index: u32, checkpoint: &Checkpoint) {
+    let key = (CHECKPOINT_PREFIX, account.clone(), index);
+    env.storage().persistent().set(&key, checkpoint);
+}
+
+pub fn get_checkpoint_count(env: &Env, account: &Address) -> u32 {
+    let key = (CHECKPOINT_COUNT_PREFIX, account.clone());
+    env.storage().persistent().get(&key).unwrap_or(0)
+}
+
+pub fn set_checkpoint_count(env: &Env, account: &Address, count: u32) {
+    let key = (CHECKPOINT_COUNT_PREFIX, account.clone());
+    env.storage().persistent().set(&key, &count);
+}
+
+/// Checkpoint `index` of the total locked voting power history.
+pub fn get_total_checkpoint(env: &Env, index: u32) -> Option<Checkpoint> {
+    let key = (TOTAL_CHECKPOINT_PREFIX, index);
+    env.storage().persistent().get(&key)
+}
+
+pub fn set_total_checkpoint(env: &Env, index: u32, checkpoint: &Checkpoint) {
+    let key = (TOTAL_CHECKPOINT_PREFIX, index);
+    env.storage().persistent().set(&key, checkpoint);
+}
+
+pub fn get_total_checkpoint_count(env: &Env) -> u32 {
+    env.storage().persistent().get(&TOTAL_CHECKPOINT_COUNT_KEY).unwrap_or(0)
+}
+
+pub fn set_total_checkpoint_count(env: &Env, count: u32) {
+    env.storage().persistent().set(&TOTAL_CHECKPOINT_COUNT_KEY, &count);
+}
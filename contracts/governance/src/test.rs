@@ -0,0 +1,643 @@
+#![cfg(test)]
+extern crate std;
+
+use super::*;
+use soroban_sdk::{
+    contract, contractimpl, symbol_short,
+    testutils::{Address as _, Ledger},
+    token::{StellarAssetClient, TokenClient},
+    vec, Address, Env, IntoVal, String,
+};
+
+const VOTING_PERIOD: u32 = 100;
+const TIMELOCK: u32 = 50;
+
+#[contract]
+pub struct MockTarget;
+
+#[contractimpl]
+impl MockTarget {
+    pub fn pause(env: Env) {
+        env.storage().instance().set(&symbol_short!("paused"), &true);
+    }
+
+    pub fn is_paused(env: Env) -> bool {
+        env.storage().instance().get(&symbol_short!("paused")).unwrap_or(false)
+    }
+
+    pub fn slash(env: Env, artist: Address, amount: i128) {
+        env.storage().instance().set(&symbol_short!("slashed"), &(artist, amount));
+    }
+
+    pub fn slashed(env: Env) -> Option<(Address, i128)> {
+        env.storage().instance().get(&symbol_short!("slashed"))
+    }
+
+    pub fn fail(_env: Env) {
+        panic!("action failed");
+    }
+}
+
+struct Setup {
+    env: Env,
+    client: GovernanceContractClient<'static>,
+    token: TokenClient<'static>,
+    token_admin: StellarAssetClient<'static>,
+    target: MockTargetClient<'static>,
+    proposer: Address,
+    alice: Address,
+    bob: Address,
+}
+
+#[allow(deprecated)]
+fn setup() -> Setup {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, GovernanceContract);
+    let client = GovernanceContractClient::new(&env, &contract_id);
+    let token_address = env.register_stellar_asset_contract(Address::generate(&env));
+    let token = TokenClient::new(&env, &token_address);
+    let token_admin = StellarAssetClient::new(&env, &token_address);
+    let target = MockTargetClient::new(&env, &env.register_contract(None, MockTarget));
+
+    let admin = Address::generate(&env);
+    let proposer = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    client.initialize(
+        &admin,
+        &token.address,
+        &VOTING_PERIOD,
+        &TIMELOCK,
+        &2_000, // 20% quorum
+        &500,
+    );
+    for (function, arity) in [("pause", 0), ("slash", 2), ("fail", 0)] {
+        client.register_action(&target.address, &String::from_str(&env, function), &arity);
+    }
+
+    for (account, amount) in [(&proposer, 1_000), (&alice, 4_000), (&bob, 5_000)] {
+        token_admin.mint(account, &amount);
+        client.deposit(account, &amount);
+    }
+    // Deposits count from the ledger after they are made.
+    advance_ledgers(&env, 1);
+
+    Setup {
+        env,
+        client,
+        token,
+        token_admin,
+        target,
+        proposer,
+        alice,
+        bob,
+    }
+}
+
+fn pause_action(s: &Setup) -> ProposalAction {
+    ProposalAction {
+        contract: s.target.address.clone(),
+        function: String::from_str(&s.env, "pause"),
+        args: Vec::new(&s.env),
+    }
+}
+
+fn propose(s: &Setup, actions: Vec<ProposalAction>) -> String {
+    s.client.create_proposal(
+        &s.proposer,
+        &String::from_str(&s.env, "Pause the target"),
+        &actions,
+    )
+}
+
+fn advance_ledgers(env: &Env, ledgers: u32) {
+    env.ledger().with_mut(|li| {
+        li.sequence_number += ledgers;
+    });
+}
+
+#[test]
+fn test_full_proposal_lifecycle() {
+    let s = setup();
+    let proposal_id = propose(&s, vec![&s.env, pause_action(&s)]);
+    assert_eq!(proposal_id, String::from_str(&s.env, "1"));
+
+    assert_eq!(s.client.cast_vote(&s.alice, &proposal_id, &true), 4_000);
+    assert_eq!(s.client.cast_vote(&s.proposer, &proposal_id, &false), 1_000);
+
+    advance_ledgers(&s.env, VOTING_PERIOD + 1);
+    assert_eq!(s.client.finalize(&proposal_id), ProposalStatus::Passed);
+
+    assert_eq!(
+        s.client.try_execute(&s.alice, &proposal_id),
+        Err(Ok(Error::TimelockNotExpired))
+    );
+
+    advance_ledgers(&s.env, TIMELOCK);
+    s.client.execute(&s.alice, &proposal_id);
+
+    assert!(s.target.is_paused());
+    assert_eq!(
+        s.client.get_proposal(&proposal_id).status,
+        ProposalStatus::Executed
+    );
+    assert_eq!(
+        s.client.try_execute(&s.alice, &proposal_id),
+        Err(Ok(Error::AlreadyExecuted))
+    );
+}
+
+#[test]
+fn test_create_proposal_validation() {
+    let s = setup();
+    let poor = Address::generate(&s.env);
+    s.token_admin.mint(&poor, &499);
+    s.client.deposit(&poor, &499);
+    advance_ledgers(&s.env, 1);
+
+    assert_eq!(
+        s.client.try_create_proposal(
+            &poor,
+            &String::from_str(&s.env, "Pause"),
+            &vec![&s.env, pause_action(&s)],
+        ),
+        Err(Ok(Error::InsufficientVotingPower))
+    );
+    assert_eq!(
+        s.client.try_create_proposal(
+            &s.proposer,
+            &String::from_str(&s.env, ""),
+            &vec![&s.env, pause_action(&s)],
+        ),
+        Err(Ok(Error::InvalidDescription))
+    );
+
+    let bad_action = ProposalAction {
+        contract: s.target.address.clone(),
+        function: String::from_str(&s.env, "not a function"),
+        args: Vec::new(&s.env),
+    };
+    assert_eq!(
+        s.client.try_create_proposal(
+            &s.proposer,
+            &String::from_str(&s.env, "Pause"),
+            &vec![&s.env, bad_action],
+        ),
+        Err(Ok(Error::InvalidAction))
+    );
+}
+
+#[test]
+fn test_voting_rules() {
+    let s = setup();
+    let proposal_id = propose(&s, vec![&s.env, pause_action(&s)]);
+    let nobody = Address::generate(&s.env);
+
+    s.client.cast_vote(&s.alice, &proposal_id, &true);
+    assert_eq!(
+        s.client.try_cast_vote(&s.alice, &proposal_id, &false),
+        Err(Ok(Error::AlreadyVoted))
+    );
+    assert_eq!(
+        s.client.try_cast_vote(&nobody, &proposal_id, &true),
+        Err(Ok(Error::InsufficientVotingPower))
+    );
+    assert_eq!(
+        s.client.try_finalize(&proposal_id),
+        Err(Ok(Error::VotingStillOpen))
+    );
+
+    advance_ledgers(&s.env, VOTING_PERIOD + 1);
+    assert_eq!(
+        s.client.try_cast_vote(&s.bob, &proposal_id, &true),
+        Err(Ok(Error::VotingClosed))
+    );
+
+    let vote = s.client.get_vote(&proposal_id, &s.alice).unwrap();
+    assert!(vote.support);
+    assert_eq!(vote.voting_power, 4_000);
+}
+
+#[test]
+fn test_proposal_rejected_without_quorum() {
+    let s = setup();
+    let proposal_id = propose(&s, vec![&s.env, pause_action(&s)]);
+
+    // 1_000 of 10_000 locked is below the 20% quorum.
+    s.client.cast_vote(&s.proposer, &proposal_id, &true);
+    advance_ledgers(&s.env, VOTING_PERIOD + 1);
+
+    assert_eq!(s.client.finalize(&proposal_id), ProposalStatus::Rejected);
+    advance_ledgers(&s.env, TIMELOCK);
+    assert_eq!(
+        s.client.try_execute(&s.proposer, &proposal_id),
+        Err(Ok(Error::ProposalNotPassed))
+    );
+    assert!(!s.target.is_paused());
+}
+
+#[test]
+fn test_proposal_rejected_by_majority() {
+    let s = setup();
+    let proposal_id = propose(&s, vec![&s.env, pause_action(&s)]);
+
+    s.client.cast_vote(&s.alice, &proposal_id, &true);
+    s.client.cast_vote(&s.bob, &proposal_id, &false);
+    advance_ledgers(&s.env, VOTING_PERIOD + 1);
+
+    assert_eq!(s.client.finalize(&proposal_id), ProposalStatus::Rejected);
+}
+
+#[test]
+fn test_failing_action_reverts_execution() {
+    let s = setup();
+    let fail = ProposalAction {
+        contract: s.target.address.clone(),
+        function: String::from_str(&s.env, "fail"),
+        args: Vec::new(&s.env),
+    };
+    let proposal_id = propose(&s, vec![&s.env, pause_action(&s), fail]);
+
+    s.client.cast_vote(&s.bob, &proposal_id, &true);
+    advance_ledgers(&s.env, VOTING_PERIOD + 1);
+    s.client.finalize(&proposal_id);
+    advance_ledgers(&s.env, TIMELOCK);
+
+    assert!(s.client.try_execute(&s.bob, &proposal_id).is_err());
+    assert!(!s.target.is_paused());
+    assert_eq!(
+        s.client.get_proposal(&proposal_id).status,
+        ProposalStatus::Passed
+    );
+}
+
+#[test]
+fn test_cancel_proposal() {
+    let s = setup();
+    let proposal_id = propose(&s, vec![&s.env, pause_action(&s)]);
+
+    assert_eq!(
+        s.client.try_cancel(&s.alice, &proposal_id),
+        Err(Ok(Error::Unauthorized))
+    );
+
+    s.client.cancel(&s.proposer, &proposal_id);
+    assert_eq!(
+        s.client.get_proposal(&proposal_id).status,
+        ProposalStatus::Cancelled
+    );
+    assert_eq!(
+        s.client.try_cast_vote(&s.alice, &proposal_id, &true),
+        Err(Ok(Error::VotingClosed))
+    );
+    assert_eq!(
+        s.client.try_cancel(&s.proposer, &proposal_id),
+        Err(Ok(Error::AlreadyCancelled))
+    );
+}
+
+#[test]
+fn test_deposit_and_withdraw() {
+    let s = setup();
+    assert_eq!(s.client.get_locked(&s.alice), 4_000);
+    assert_eq!(s.token.balance(&s.alice), 0);
+
+    s.client.withdraw(&s.alice, &1_500);
+    assert_eq!(s.client.get_locked(&s.alice), 2_500);
+    assert_eq!(s.client.get_votes(&s.alice), 2_500);
+    assert_eq!(s.token.balance(&s.alice), 1_500);
+
+    assert_eq!(
+        s.client.try_withdraw(&s.alice, &2_501),
+        Err(Ok(Error::InsufficientVotingPower))
+    );
+    assert_eq!(
+        s.client.try_deposit(&s.alice, &0),
+        Err(Ok(Error::InvalidAmount))
+    );
+}
+
+#[test]
+fn test_voting_power_is_snapshotted() {
+    let s = setup();
+    let proposal_id = propose(&s, vec![&s.env, pause_action(&s)]);
+
+    // Tokens bought and locked after the proposal carry no weight on it.
+    let latecomer = Address::generate(&s.env);
+    s.token_admin.mint(&latecomer, &50_000);
+    s.client.deposit(&latecomer, &50_000);
+    assert_eq!(
+        s.client.try_cast_vote(&latecomer, &proposal_id, &true),
+        Err(Ok(Error::InsufficientVotingPower))
+    );
+
+    // Withdrawing after the snapshot does not reduce weight on it either.
+    s.client.withdraw(&s.alice, &4_000);
+    assert_eq!(s.client.cast_vote(&s.alice, &proposal_id, &true), 4_000);
+
+    // Quorum is measured against the power locked at the snapshot.
+    advance_ledgers(&s.env, VOTING_PERIOD + 1);
+    assert_eq!(s.client.finalize(&proposal_id), ProposalStatus::Passed);
+}
+
+#[test]
+fn test_votes_at_reads_checkpoint_history() {
+    let s = setup();
+    let start = s.env.ledger().sequence();
+
+    // One checkpoint per ledger; the second deposit in a ledger updates it.
+    s.token_admin.mint(&s.alice, &1_000);
+    for _ in 0..5 {
+        s.client.deposit(&s.alice, &100);
+        s.client.deposit(&s.alice, &100);
+        advance_ledgers(&s.env, 10);
+    }
+
+    assert_eq!(s.client.get_votes_at(&s.alice, &(start - 1)), 4_000);
+    for step in 0..5 {
+        let expected = 4_000 + 200 * (step as i128 + 1);
+        assert_eq!(s.client.get_votes_at(&s.alice, &(start + step * 10)), expected);
+        assert_eq!(s.client.get_votes_at(&s.alice, &(start + step * 10 + 9)), expected);
+    }
+    assert_eq!(s.client.get_votes(&s.alice), 5_000);
+    assert_eq!(s.client.get_votes_at(&Address::generate(&s.env), &start), 0);
+}
+
+#[test]
+fn test_transitive_delegation() {
+    let s = setup();
+    // proposer -> alice -> bob
+    s.client.delegate(&s.proposer, &s.alice);
+    s.client.delegate(&s.alice, &s.bob);
+
+    assert_eq!(s.client.get_votes(&s.proposer), 0);
+    assert_eq!(s.client.get_votes(&s.alice), 0);
+    assert_eq!(s.client.get_votes(&s.bob), 10_000);
+
+    // Power deposited later follows the chain as well.
+    s.token_admin.mint(&s.proposer, &500);
+    s.client.deposit(&s.proposer, &500);
+    assert_eq!(s.client.get_votes(&s.bob), 10_500);
+
+    s.client.undelegate(&s.alice);
+    assert_eq!(s.client.get_votes(&s.alice), 5_500);
+    assert_eq!(s.client.get_votes(&s.bob), 5_000);
+    assert_eq!(
+        s.client.try_undelegate(&s.alice),
+        Err(Ok(Error::InvalidDelegatee))
+    );
+}
+
+#[test]
+fn test_delegation_rejects_self_and_cycles() {
+    let s = setup();
+    assert_eq!(
+        s.client.try_delegate(&s.alice, &s.alice),
+        Err(Ok(Error::SelfDelegation))
+    );
+
+    s.client.delegate(&s.alice, &s.bob);
+    s.client.delegate(&s.bob, &s.proposer);
+    assert_eq!(
+        s.client.try_delegate(&s.proposer, &s.alice),
+        Err(Ok(Error::InvalidDelegatee))
+    );
+    assert_eq!(s.client.get_votes(&s.proposer), 10_000);
+}
+
+#[test]
+fn test_delegation_chain_depth_counts_upstream() {
+    let s = setup();
+    let accounts: std::vec::Vec<Address> = (0..=voting::MAX_DELEGATION_DEPTH + 1)
+        .map(|_| Address::generate(&s.env))
+        .collect();
+    s.token_admin.mint(&accounts[0], &100);
+    s.client.deposit(&accounts[0], &100);
+
+    // Grow the chain from its tail, each link pointing at a fresh account.
+    for i in 0..voting::MAX_DELEGATION_DEPTH as usize {
+        s.client.delegate(&accounts[i], &accounts[i + 1]);
+    }
+    let tail = voting::MAX_DELEGATION_DEPTH as usize;
+    assert_eq!(s.client.get_votes(&accounts[tail]), 100);
+    assert_eq!(
+        s.client.try_delegate(&accounts[tail], &accounts[tail + 1]),
+        Err(Ok(Error::InvalidDelegatee))
+    );
+
+    // Shortening the chain at its head frees up room at the tail.
+    s.client.undelegate(&accounts[0]);
+    s.client.delegate(&accounts[tail], &accounts[tail + 1]);
+    assert_eq!(s.client.get_votes(&accounts[0]), 100);
+    assert_eq!(
+        s.client.try_delegate(&accounts[0], &accounts[1]),
+        Err(Ok(Error::InvalidDelegatee))
+    );
+}
+
+#[test]
+fn test_redelegation_only_affects_future_proposals() {
+    let s = setup();
+    s.client.delegate(&s.alice, &s.bob);
+    advance_ledgers(&s.env, 1);
+    let first = propose(&s, vec![&s.env, pause_action(&s)]);
+
+    s.client.delegate(&s.alice, &s.proposer);
+    advance_ledgers(&s.env, 1);
+    let second = propose(&s, vec![&s.env, pause_action(&s)]);
+
+    assert_eq!(s.client.cast_vote(&s.bob, &first, &true), 9_000);
+    assert_eq!(s.client.cast_vote(&s.proposer, &first, &false), 1_000);
+    assert_eq!(s.client.cast_vote(&s.bob, &second, &true), 5_000);
+    assert_eq!(s.client.cast_vote(&s.proposer, &second, &false), 5_000);
+    assert_eq!(
+        s.client.try_cast_vote(&s.alice, &second, &true),
+        Err(Ok(Error::InsufficientVotingPower))
+    );
+
+    let record = s.client.get_delegation(&s.alice).unwrap();
+    assert_eq!(record.delegatee, s.proposer);
+}
+
+#[test]
+fn test_action_with_arguments() {
+    let s = setup();
+    let artist = Address::generate(&s.env);
+    let slash = ProposalAction {
+        contract: s.target.address.clone(),
+        function: String::from_str(&s.env, "slash"),
+        args: vec![&s.env, artist.into_val(&s.env), 250_i128.into_val(&s.env)],
+    };
+    let proposal_id = propose(&s, vec![&s.env, pause_action(&s), slash]);
+
+    s.client.cast_vote(&s.bob, &proposal_id, &true);
+    advance_ledgers(&s.env, VOTING_PERIOD + 1);
+    s.client.finalize(&proposal_id);
+    advance_ledgers(&s.env, TIMELOCK);
+    s.client.execute(&s.bob, &proposal_id);
+
+    assert!(s.target.is_paused());
+    assert_eq!(s.target.slashed(), Some((artist, 250)));
+}
+
+#[test]
+fn test_wrong_arguments_rejected_at_creation() {
+    let s = setup();
+    let slash = ProposalAction {
+        contract: s.target.address.clone(),
+        function: String::from_str(&s.env, "slash"),
+        args: vec![&s.env, 250_i128.into_val(&s.env)],
+    };
+    assert_eq!(
+        s.client.try_create_proposal(
+            &s.proposer,
+            &String::from_str(&s.env, "Slash"),
+            &vec![&s.env, pause_action(&s), slash],
+        ),
+        Err(Ok(Error::InvalidAction))
+    );
+
+    // Functions the admin has not registered cannot be proposed at all.
+    let unregistered = ProposalAction {
+        contract: s.target.address.clone(),
+        function: String::from_str(&s.env, "is_paused"),
+        args: Vec::new(&s.env),
+    };
+    assert_eq!(
+        s.client.try_create_proposal(
+            &s.proposer,
+            &String::from_str(&s.env, "Check"),
+            &vec![&s.env, unregistered],
+        ),
+        Err(Ok(Error::InvalidAction))
+    );
+    assert_eq!(
+        s.client.get_action_arity(&s.target.address, &String::from_str(&s.env, "slash")),
+        Some(2)
+    );
+}
+
+#[test]
+fn test_too_many_arguments_rejected() {
+    let s = setup();
+    let mut args = Vec::new(&s.env);
+    for i in 0..11_u32 {
+        args.push_back(i.into_val(&s.env));
+    }
+    let action = ProposalAction {
+        contract: s.target.address.clone(),
+        function: String::from_str(&s.env, "pause"),
+        args,
+    };
+    assert_eq!(
+        s.client.try_create_proposal(
+            &s.proposer,
+            &String::from_str(&s.env, "Pause"),
+            &vec![&s.env, action],
+        ),
+        Err(Ok(Error::InvalidAction))
+    );
+    assert_eq!(
+        s.client.try_register_action(&s.target.address, &String::from_str(&s.env, "pause"), &11),
+        Err(Ok(Error::InvalidAction))
+    );
+}
+
+#[test]
+fn test_registry_changes_do_not_block_passed_proposals() {
+    let s = setup();
+    let artist = Address::generate(&s.env);
+    let slash = ProposalAction {
+        contract: s.target.address.clone(),
+        function: String::from_str(&s.env, "slash"),
+        args: vec![&s.env, artist.into_val(&s.env), 250_i128.into_val(&s.env)],
+    };
+    let proposal_id = propose(&s, vec![&s.env, pause_action(&s), slash]);
+    s.client.cast_vote(&s.bob, &proposal_id, &true);
+    advance_ledgers(&s.env, VOTING_PERIOD + 1);
+    s.client.finalize(&proposal_id);
+
+    // The admin changes the registry while the proposal waits out its timelock.
+    s.client.register_action(&s.target.address, &String::from_str(&s.env, "slash"), &3);
+    s.client.unregister_action(&s.target.address, &String::from_str(&s.env, "pause"));
+    assert_eq!(
+        s.client.get_action_arity(&s.target.address, &String::from_str(&s.env, "pause")),
+        None
+    );
+    assert_eq!(
+        s.client.try_create_proposal(
+            &s.proposer,
+            &String::from_str(&s.env, "Pause again"),
+            &vec![&s.env, pause_action(&s)],
+        ),
+        Err(Ok(Error::InvalidAction))
+    );
+
+    // The passed proposal runs with the arguments it was created with.
+    advance_ledgers(&s.env, TIMELOCK);
+    s.client.execute(&s.bob, &proposal_id);
+    assert!(s.target.is_paused());
+    assert_eq!(s.target.slashed(), Some((artist, 250)));
+}
+
+#[test]
+fn test_proposals_manage_registry_after_admin_handover() {
+    let s = setup();
+    let governance = s.client.address.clone();
+    s.client.set_admin(&governance);
+    assert_eq!(s.client.get_config().admin, governance);
+
+    let self_action = |function: &str, args: Vec<Val>| ProposalAction {
+        contract: governance.clone(),
+        function: String::from_str(&s.env, function),
+        args,
+    };
+    let actions = vec![
+        &s.env,
+        self_action(
+            "register_action",
+            vec![
+                &s.env,
+                s.target.address.into_val(&s.env),
+                String::from_str(&s.env, "is_paused").into_val(&s.env),
+                0_u32.into_val(&s.env),
+            ],
+        ),
+        self_action(
+            "unregister_action",
+            vec![
+                &s.env,
+                s.target.address.into_val(&s.env),
+                String::from_str(&s.env, "fail").into_val(&s.env),
+            ],
+        ),
+    ];
+    let proposal_id = propose(&s, actions);
+
+    // Self actions are checked against their fixed arity too.
+    assert_eq!(
+        s.client.try_create_proposal(
+            &s.proposer,
+            &String::from_str(&s.env, "Bad handover"),
+            &vec![&s.env, self_action("set_admin", Vec::new(&s.env))],
+        ),
+        Err(Ok(Error::InvalidAction))
+    );
+
+    s.client.cast_vote(&s.bob, &proposal_id, &true);
+    advance_ledgers(&s.env, VOTING_PERIOD + 1);
+    s.client.finalize(&proposal_id);
+    advance_ledgers(&s.env, TIMELOCK);
+    s.client.execute(&s.bob, &proposal_id);
+
+    assert_eq!(
+        s.client.get_action_arity(&s.target.address, &String::from_str(&s.env, "is_paused")),
+        Some(0)
+    );
+    assert_eq!(
+        s.client.get_action_arity(&s.target.address, &String::from_str(&s.env, "fail")),
+        None
+    );
+}
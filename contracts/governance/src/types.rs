@@ -1,4 +1,4 @@
-use soroban_sdk::{contracttype, Address, String, Vec};
+use soroban_sdk::{contracttype, Address, String, Val, Vec};
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -15,6 +15,7 @@ pub enum ProposalStatus {
 pub struct ProposalAction {
     pub contract: Address,
     pub function: String,
+    pub args: Vec<Val>,               // passed to `function` in order
 }
 
 #[contracttype]
@@ -26,10 +27,11 @@ pub struct Proposal {
     pub actions: Vec<ProposalAction>,
     pub votes_for: i128,
     pub votes_against: i128,
-    pub created_at: u64,
-    pub voting_ends_at: u64,
-    pub execution_available_at: u64,
+    pub created_at: u64,              // ledger sequence
+    pub voting_ends_at: u64,          // ledger sequence
+    pub execution_available_at: u64,  // ledger sequence
     pub status: ProposalStatus,
+    pub snapshot_ledger: u32,         // voting power is read as of this ledger
 }
 
 #[contracttype]
@@ -59,4 +61,12 @@ pub struct GovernanceConfig {
     pub timelock_ledgers: u32,
     pub quorum_basis_points: u32,  // e.g. 1000 = 10%
     pub proposal_threshold: i128,  // min tokens to create proposal
-}
\ No newline at end of file
+}
+
+/// Voting power held by an account from ledger `ledger` onwards.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Checkpoint {
+    pub ledger: u32,
+    pub votes: i128,
+}
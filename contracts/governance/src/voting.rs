@@ -0,0 +1,179 @@
+use soroban_sdk::{Address, Env, Vec};
+use crate::errors::Error;
+use crate::storage;
+use crate::types::Checkpoint;
+
+/// Most delegation hops allowed on any chain.
+pub const MAX_DELEGATION_DEPTH: u32 = 8;
+
+/// Voting power `account` held as of `ledger`.
+pub fn votes_at(env: &Env, account: &Address, ledger: u32) -> i128 {
+    let count = storage::get_checkpoint_count(env, account);
+    search_checkpoints(count, ledger, |index| storage::get_checkpoint(env, account, index))
+}
+
+/// Total locked voting power as of `ledger`.
+pub fn total_votes_at(env: &Env, ledger: u32) -> i128 {
+    let count = storage::get_total_checkpoint_count(env);
+    search_checkpoints(count, ledger, |index| storage::get_total_checkpoint(env, index))
+}
+
+/// Binary search the `count` checkpoints returned by `load` for the last one
+/// at or before `ledger`, reading one entry per step.
+fn search_checkpoints<F>(count: u32, ledger: u32, load: F) -> i128
+where
+    F: Fn(u32) -> Option<Checkpoint>,
+{
+    let mut low = 0;
+    let mut high = count;
+    while low < high {
+        let mid = (low + high) / 2;
+        match load(mid) {
+            Some(checkpoint) if checkpoint.ledger <= ledger => low = mid + 1,
+            _ => high = mid,
+        }
+    }
+    if low == 0 {
+        0
+    } else {
+        load(low - 1).map(|c| c.votes).unwrap_or(0)
+    }
+}
+
+pub fn current_votes(env: &Env, account: &Address) -> i128 {
+    match storage::get_checkpoint_count(env, account) {
+        0 => 0,
+        count => storage::get_checkpoint(env, account, count - 1)
+            .map(|c| c.votes)
+            .unwrap_or(0),
+    }
+}
+
+/// Work out where the checkpoint after applying `delta` to `last` goes:
+/// over `last` when it was written this ledger, otherwise at `count`.
+fn next_checkpoint(env: &Env, count: u32, last: Option<Checkpoint>, delta: i128) -> (u32, Checkpoint) {
+    let ledger = env.ledger().sequence();
+    let votes = last.as_ref().map(|c| c.votes).unwrap_or(0) + delta;
+    let checkpoint = Checkpoint { ledger, votes };
+    match last {
+        Some(last) if last.ledger == ledger => (count - 1, checkpoint),
+        _ => (count, checkpoint),
+    }
+}
+
+fn add_votes(env: &Env, account: &Address, delta: i128) {
+    let count = storage::get_checkpoint_count(env, account);
+    let last = if count == 0 { None } else { storage::get_checkpoint(env, account, count - 1) };
+    let (index, checkpoint) = next_checkpoint(env, count, last, delta);
+    storage::set_checkpoint(env, account, index, &checkpoint);
+    if index == count {
+        storage::set_checkpoint_count(env, account, count + 1);
+    }
+}
+
+pub fn add_total_votes(env: &Env, delta: i128) {
+    let count = storage::get_total_checkpoint_count(env);
+    let last = if count == 0 { None } else { storage::get_total_checkpoint(env, count - 1) };
+    let (index, checkpoint) = next_checkpoint(env, count, last, delta);
+    storage::set_total_checkpoint(env, index, &checkpoint);
+    if index == count {
+        storage::set_total_checkpoint_count(env, count + 1);
+    }
+}
+
+/// Push `delta` of flow into `account` and along its delegation chain,
+/// crediting the votes of the account at the end of the chain.
+pub fn move_flow(env: &Env, account: &Address, delta: i128) {
+    let mut node = account.clone();
+    loop {
+        storage::set_flow(env, &node, storage::get_flow(env, &node) + delta);
+        match storage::get_delegation(env, &node) {
+            Some(record) => node = record.delegatee,
+            None => {
+                add_votes(env, &node, delta);
+                return;
+            }
+        }
+    }
+}
+
+/// Reject a delegation from `delegator` to `delegatee` that would close a
+/// cycle or put any account on a chain longer than `MAX_DELEGATION_DEPTH`,
+/// counting both the chains that lead into `delegator` and the one that
+/// continues past `delegatee`.
+pub fn check_delegation_chain(
+    env: &Env,
+    delegator: &Address,
+    delegatee: &Address,
+) -> Result<(), Error> {
+    let mut node = delegatee.clone();
+    let mut depth = upstream_depth(env, delegator) + 1;
+    loop {
+        if node == *delegator || depth > MAX_DELEGATION_DEPTH {
+            return Err(Error::InvalidDelegatee);
+        }
+        match storage::get_delegation(env, &node) {
+            Some(record) => {
+                depth += 1;
+                node = record.delegatee;
+            }
+            None => return Ok(()),
+        }
+    }
+}
+
+/// Length of the longest delegation chain ending at `account`.
+fn upstream_depth(env: &Env, account: &Address) -> u32 {
+    let inbound = storage::get_inbound(env, account);
+    let mut depth = inbound.len();
+    while depth > 0 && inbound.get_unchecked(depth - 1) == 0 {
+        depth -= 1;
+    }
+    depth
+}
+
+/// Add (`sign` = 1) or remove (`sign` = -1) `account` and everything that
+/// delegates into it from the inbound counts along its delegation chain.
+fn shift_inbound(env: &Env, account: &Address, sign: i64) {
+    let upstream = storage::get_inbound(env, account);
+    let mut node = account.clone();
+    let mut distance = 0;
+    while let Some(record) = storage::get_delegation(env, &node) {
+        node = record.delegatee;
+        let mut inbound = storage::get_inbound(env, &node);
+        bump(&mut inbound, distance, sign);
+        for (d, count) in upstream.iter().enumerate() {
+            bump(&mut inbound, distance + 1 + d as u32, sign * count as i64);
+        }
+        storage::set_inbound(env, &node, &inbound);
+        distance += 1;
+    }
+}
+
+fn bump(inbound: &mut Vec<u32>, index: u32, delta: i64) {
+    while inbound.len() <= index {
+        inbound.push_back(0);
+    }
+    let count = inbound.get_unchecked(index) as i64 + delta;
+    inbound.set(index, count as u32);
+}
+
+/// Detach `account`'s flow from wherever it currently ends up.
+pub fn detach(env: &Env, account: &Address) {
+    let flow = storage::get_flow(env, account);
+    shift_inbound(env, account, -1);
+    match storage::get_delegation(env, account) {
+        Some(record) => move_flow(env, &record.delegatee, -flow),
+        None => add_votes(env, account, -flow),
+    }
+}
+
+/// Attach `account`'s flow to its current delegatee, or to itself.
+pub fn attach(env: &Env, account: &Address) {
+    let flow = storage::get_flow(env, account);
+    shift_inbound(env, account, 1);
+    match storage::get_delegation(env, account) {
+        Some(record) => move_flow(env, &record.delegatee, flow),
+        None => add_votes(env, account, flow),
+    }
+}
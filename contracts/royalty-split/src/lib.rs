@@ -1,7 +1,7 @@
 #![no_std]
 
 use soroban_sdk::{
-    contract, contracterror, contractimpl, contracttype, symbol_short, Address, Env, String, Symbol,
+    contract, contracterror, contractimpl, contracttype, symbol_short, token, Address, Env, String,
     Vec,
 };
 
@@ -14,12 +14,53 @@ pub enum Error {
     TrackNotFound = 3,
     CollaboratorNotFound = 4,
     AlreadyExists = 5,
+    InvalidAmount = 6,
+    Unauthorized = 7,
+    VersionNotFound = 8,
 }
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum DataKey {
     Split(String),
+    Owner(String),           // track_id -> owner who may change the split
+    ConsentRequired(String), // track_id -> collaborators must sign share reductions
+    SplitVersion(String, u32), // track_id, version -> SplitVersion
+    VersionCount(String),      // track_id -> latest version number
+}
+
+/// A split configuration as it stood from `effective_from` until the next version.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SplitVersion {
+    pub version: u32,
+    pub collaborators: Vec<(Address, u32)>,
+    pub effective_from: u64, // Ledger timestamp
+}
+
+/// Shares computed for a payout and the split version they were based on.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Distribution {
+    pub version: u32,
+    pub shares: Vec<(Address, i128)>,
+}
+
+/// A single edit to a track's split, used by `update_shares`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ShareChange {
+    Add(Address, u32),      // New collaborator with a share in basis points
+    Remove(Address),
+    Reweight(Address, u32), // Existing collaborator's new share in basis points
+}
+
+/// How `update_shares` makes the result total 10,000 basis points.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum UpdateMode {
+    Rebalance, // Scale untouched collaborators to fill the remainder
+    Exact,     // Reject the update unless it already totals 10,000
 }
 
 #[contract]
@@ -28,11 +69,29 @@ pub struct RoyaltySplit;
 #[contractimpl]
 impl RoyaltySplit {
     /// Set the whole royalty split for a track. Total must be 10,000 basis points.
+    /// The first caller to set a new track's split becomes its owner; after
+    /// that only the owner may change it. A split stored before tracks had
+    /// owners is not up for grabs: its collaborators hand it to an owner
+    /// through `claim_ownership`.
     pub fn set_royalty_split(
         env: Env,
+        owner: Address,
         track_id: String,
         collaborators: Vec<(Address, u32)>,
     ) -> Result<(), Error> {
+        owner.require_auth();
+        match get_owner(&env, &track_id) {
+            Some(current) if current != owner => return Err(Error::Unauthorized),
+            Some(_) => {}
+            None if env.storage().persistent().has(&DataKey::Split(track_id.clone())) => {
+                return Err(Error::Unauthorized)
+            }
+            None => env
+                .storage()
+                .persistent()
+                .set(&DataKey::Owner(track_id.clone()), &owner),
+        }
+
         let mut total_bp: u32 = 0;
         for param in collaborators.clone() {
             let (_, bp) = param;
@@ -46,9 +105,15 @@ impl RoyaltySplit {
             return Err(Error::TotalNot10000);
         }
 
-        env.storage()
+        let old: Option<Vec<(Address, u32)>> = env
+            .storage()
             .persistent()
-            .set(&DataKey::Split(track_id.clone()), &collaborators);
+            .get(&DataKey::Split(track_id.clone()));
+        if let Some(old) = old {
+            require_consent(&env, &track_id, &owner, &old, &collaborators);
+        }
+
+        store_split(&env, &track_id, &collaborators);
 
         // Emit event for the full split update
         env.events().publish(
@@ -59,89 +124,456 @@ impl RoyaltySplit {
         Ok(())
     }
 
-    /// Update a specific collaborator's split. Adjusts other collaborators proportionally?
-    /// Actually, let's keep it simple: Replace the whole split if it's more complex,
-    /// but let's provide a way to update one.
-    /// Safest update flow: update_collaborator_share(track_id, collab, new_share, other_collab_to_offset)
-    /// but the issue says "safer update and removal flows".
-    /// Let's add simple add/update and remove that check for 10000 total.
-    
+    /// Apply a set of collaborator changes to a track's split in one step.
+    /// Added and re-weighted collaborators get exactly the requested share.
+    /// With `UpdateMode::Rebalance` the untouched collaborators are scaled
+    /// proportionally to fill the rest of the 10,000 bp; with
+    /// `UpdateMode::Exact` the result must already total 10,000 bp. Nothing
+    /// is stored unless the whole update is valid.
+    pub fn update_shares(
+        env: Env,
+        owner: Address,
+        track_id: String,
+        changes: Vec<ShareChange>,
+        mode: UpdateMode,
+    ) -> Result<Vec<(Address, u32)>, Error> {
+        require_owner(&env, &track_id, &owner)?;
+
+        let old: Vec<(Address, u32)> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Split(track_id.clone()))
+            .ok_or(Error::TrackNotFound)?;
+
+        let mut collaborators = old.clone();
+        let mut pinned: Vec<Address> = Vec::new(&env);
+
+        for change in changes.iter() {
+            match change {
+                ShareChange::Add(collab, bp) => {
+                    check_share(bp)?;
+                    if find_collaborator(&collaborators, &collab).is_some() {
+                        return Err(Error::AlreadyExists);
+                    }
+                    collaborators.push_back((collab.clone(), bp));
+                    pinned.push_back(collab);
+                }
+                ShareChange::Remove(collab) => {
+                    let idx = find_collaborator(&collaborators, &collab)
+                        .ok_or(Error::CollaboratorNotFound)?;
+                    collaborators.remove(idx);
+                }
+                ShareChange::Reweight(collab, bp) => {
+                    check_share(bp)?;
+                    let idx = find_collaborator(&collaborators, &collab)
+                        .ok_or(Error::CollaboratorNotFound)?;
+                    collaborators.set(idx, (collab.clone(), bp));
+                    pinned.push_back(collab);
+                }
+            }
+        }
+
+        if mode == UpdateMode::Rebalance {
+            collaborators = rebalance(&env, &collaborators, &pinned)?;
+        }
+
+        let mut total_bp: u32 = 0;
+        for (_, bp) in collaborators.iter() {
+            total_bp += bp;
+        }
+        if total_bp != 10000 {
+            return Err(Error::TotalNot10000);
+        }
+        require_consent(&env, &track_id, &owner, &old, &collaborators);
+
+        store_split(&env, &track_id, &collaborators);
+
+        // Emit (collaborator, old bp, new bp) for every share that changed
+        env.events().publish(
+            (symbol_short!("royalty"), symbol_short!("update"), track_id),
+            split_diff(&env, &old, &collaborators),
+        );
+
+        Ok(collaborators)
+    }
+
+    /// Remove a collaborator and rebalance the remaining shares proportionally.
     pub fn remove_collaborator(
         env: Env,
+        owner: Address,
         track_id: String,
         collaborator: Address,
     ) -> Result<(), Error> {
-        let mut collaborators: Vec<(Address, u32)> = env
+        let mut changes = Vec::new(&env);
+        changes.push_back(ShareChange::Remove(collaborator));
+        Self::update_shares(env, owner, track_id, changes, UpdateMode::Rebalance)?;
+        Ok(())
+    }
+
+    /// Require (or stop requiring) each collaborator to co-sign any change
+    /// that reduces or removes their share. Owner only; turning consent off
+    /// again needs every current collaborator to co-sign as well.
+    pub fn set_consent_required(
+        env: Env,
+        owner: Address,
+        track_id: String,
+        required: bool,
+    ) -> Result<(), Error> {
+        require_owner(&env, &track_id, &owner)?;
+
+        let currently_required: bool = env
+            .storage()
+            .persistent()
+            .get(&DataKey::ConsentRequired(track_id.clone()))
+            .unwrap_or(false);
+        if currently_required && !required {
+            let collaborators: Vec<(Address, u32)> = env
+                .storage()
+                .persistent()
+                .get(&DataKey::Split(track_id.clone()))
+                .unwrap_or(Vec::new(&env));
+            for (collab, _) in collaborators.iter() {
+                if collab != owner {
+                    collab.require_auth();
+                }
+            }
+        }
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::ConsentRequired(track_id), &required);
+
+        Ok(())
+    }
+
+    /// Hand a track's split over to a new owner. Owner only.
+    pub fn transfer_ownership(
+        env: Env,
+        owner: Address,
+        track_id: String,
+        new_owner: Address,
+    ) -> Result<(), Error> {
+        require_owner(&env, &track_id, &owner)?;
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::Owner(track_id.clone()), &new_owner);
+
+        env.events().publish(
+            (symbol_short!("royalty"), symbol_short!("owner"), track_id),
+            (owner, new_owner),
+        );
+
+        Ok(())
+    }
+
+    /// Give an ownerless track, whose split predates ownership, its owner.
+    /// The new owner and every collaborator on the current split must sign.
+    pub fn claim_ownership(env: Env, track_id: String, owner: Address) -> Result<(), Error> {
+        owner.require_auth();
+        if get_owner(&env, &track_id).is_some() {
+            return Err(Error::AlreadyExists);
+        }
+        let collaborators: Vec<(Address, u32)> = env
             .storage()
             .persistent()
             .get(&DataKey::Split(track_id.clone()))
             .ok_or(Error::TrackNotFound)?;
-
-        let mut found_index = None;
-        for i in 0..collaborators.len() {
-            if collaborators.get(i).unwrap().0 == collaborator {
-                found_index = Some(i);
-                break;
+        for (collab, _) in collaborators.iter() {
+            if collab != owner {
+                collab.require_auth();
             }
         }
 
-        if let Some(idx) = found_index {
-            collaborators.remove(idx);
-            
-            // Note: After removal, total will not be 10000.
-            // The caller should call set_royalty_split again or we should enforce a redistribution.
-            // Safer: only allow removal if we automatically redistribute or if we require a full update.
-            // Let's just provide a way to check total after removal.
-            // Actually, keep it simple for now: set_royalty_split is the most atomic way.
-            // If the user wants granular removal, they must ensure the remaining add up to 10k.
-            // But how? Maybe they can't.
-            // Let's just stick to requirements: events and rounding.
-        } else {
-            return Err(Error::CollaboratorNotFound);
-        }
+        env.storage()
+            .persistent()
+            .set(&DataKey::Owner(track_id.clone()), &owner);
 
-        // We only save if the total is still valid (which it won't be unless we had one collab at 10000)
-        // So granular updates are tricky for basis points.
-        // Let's just improve the error messages and events as requested.
+        env.events().publish(
+            (symbol_short!("royalty"), symbol_short!("claimed"), track_id),
+            owner,
+        );
 
         Ok(())
     }
 
+    pub fn get_owner(env: Env, track_id: String) -> Option<Address> {
+        get_owner(&env, &track_id)
+    }
+
+    /// Compute each collaborator's share of `amount` under the current split,
+    /// along with the split version used.
     pub fn distribute_royalties(
         env: Env,
         track_id: String,
         amount: i128,
-    ) -> Result<Vec<(Address, i128)>, Error> {
+    ) -> Result<Distribution, Error> {
         if amount <= 0 {
-            return Ok(Vec::new(&env));
+            return Ok(Distribution {
+                version: get_version_count(&env, &track_id),
+                shares: Vec::new(&env),
+            });
         }
 
-        let split: Vec<(Address, u32)> = env
-            .storage()
-            .persistent()
-            .get(&DataKey::Split(track_id))
-            .ok_or(Error::TrackNotFound)?;
+        compute_shares(&env, &track_id, amount)
+    }
 
-        let mut distributions = Vec::new(&env);
-        let mut total_distributed = 0;
-        let collaborators_count = split.len();
+    /// Get the split that was in force for a track at `timestamp`.
+    pub fn get_split_at(env: Env, track_id: String, timestamp: u64) -> Result<SplitVersion, Error> {
+        let count = get_version_count(&env, &track_id);
+        if count == 0 {
+            return Err(Error::TrackNotFound);
+        }
 
-        for i in 0..collaborators_count {
-            let (collab, bp) = split.get(i).unwrap();
-            
-            let share = if i == (collaborators_count - 1) {
-                // Last collaborator gets the remainder to handle rounding errors
-                amount - total_distributed
+        // Binary search for the last version effective at or before `timestamp`
+        let mut low = 1;
+        let mut high = count + 1;
+        while low < high {
+            let mid = (low + high) / 2;
+            if get_split_version(&env, &track_id, mid).effective_from <= timestamp {
+                low = mid + 1;
             } else {
-                (amount * (bp as i128)) / 10000
-            };
+                high = mid;
+            }
+        }
+        if low == 1 {
+            return Err(Error::VersionNotFound);
+        }
 
-            total_distributed += share;
-            distributions.push_back((collab, share));
+        Ok(get_split_version(&env, &track_id, low - 1))
+    }
+
+    /// Get every split configuration a track has had, oldest first.
+    pub fn get_split_versions(env: Env, track_id: String) -> Vec<SplitVersion> {
+        let mut versions = Vec::new(&env);
+        for version in 1..=get_version_count(&env, &track_id) {
+            versions.push_back(get_split_version(&env, &track_id, version));
+        }
+        versions
+    }
+
+    /// Split `amount` of `token` for a track and transfer each share from
+    /// `payer` straight to the collaborator. Returns the same breakdown as
+    /// `distribute_royalties`.
+    pub fn distribute_and_pay(
+        env: Env,
+        payer: Address,
+        track_id: String,
+        token: Address,
+        amount: i128,
+    ) -> Result<Distribution, Error> {
+        payer.require_auth();
+
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        let distributions = compute_shares(&env, &track_id, amount)?;
+        let token_client = token::Client::new(&env, &token);
+
+        for (collab, share) in distributions.shares.iter() {
+            if share > 0 {
+                token_client.transfer(&payer, &collab, &share);
+            }
+
+            // Emit one event per recipient so payouts can be indexed individually
+            env.events().publish(
+                (symbol_short!("royalty"), symbol_short!("payout"), track_id.clone()),
+                (collab, token.clone(), share),
+            );
         }
 
         Ok(distributions)
     }
 }
 
+/// Make `collaborators` the current split and record it as a new version.
+fn store_split(env: &Env, track_id: &String, collaborators: &Vec<(Address, u32)>) -> u32 {
+    let version = get_version_count(env, track_id) + 1;
+    let record = SplitVersion {
+        version,
+        collaborators: collaborators.clone(),
+        effective_from: env.ledger().timestamp(),
+    };
+
+    let storage = env.storage().persistent();
+    storage.set(&DataKey::Split(track_id.clone()), collaborators);
+    storage.set(&DataKey::SplitVersion(track_id.clone(), version), &record);
+    storage.set(&DataKey::VersionCount(track_id.clone()), &version);
+    version
+}
+
+fn get_version_count(env: &Env, track_id: &String) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::VersionCount(track_id.clone()))
+        .unwrap_or(0)
+}
+
+fn get_split_version(env: &Env, track_id: &String, version: u32) -> SplitVersion {
+    env.storage()
+        .persistent()
+        .get(&DataKey::SplitVersion(track_id.clone(), version))
+        .unwrap()
+}
+
+fn get_owner(env: &Env, track_id: &String) -> Option<Address> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Owner(track_id.clone()))
+}
+
+/// Check that `caller` owns an existing track and has signed the call.
+fn require_owner(env: &Env, track_id: &String, caller: &Address) -> Result<(), Error> {
+    caller.require_auth();
+    let owner = get_owner(env, track_id).ok_or(Error::TrackNotFound)?;
+    if owner != *caller {
+        return Err(Error::Unauthorized);
+    }
+    Ok(())
+}
+
+/// If the track requires consent, make every collaborator whose share goes
+/// down between `old` and `new` sign the call. The owner has already signed.
+fn require_consent(
+    env: &Env,
+    track_id: &String,
+    owner: &Address,
+    old: &Vec<(Address, u32)>,
+    new: &Vec<(Address, u32)>,
+) {
+    let required: bool = env
+        .storage()
+        .persistent()
+        .get(&DataKey::ConsentRequired(track_id.clone()))
+        .unwrap_or(false);
+    if !required {
+        return;
+    }
+
+    for (collab, old_bp, new_bp) in split_diff(env, old, new).iter() {
+        if new_bp < old_bp && collab != *owner {
+            collab.require_auth();
+        }
+    }
+}
+
+fn check_share(bp: u32) -> Result<(), Error> {
+    if bp == 0 || bp > 10000 {
+        return Err(Error::InvalidPercentage);
+    }
+    Ok(())
+}
+
+fn find_collaborator(collaborators: &Vec<(Address, u32)>, collab: &Address) -> Option<u32> {
+    collaborators.iter().position(|(addr, _)| addr == *collab).map(|i| i as u32)
+}
+
+/// Scale the collaborators not in `pinned` so the split totals 10,000 bp.
+/// The last scaled collaborator absorbs rounding.
+fn rebalance(
+    env: &Env,
+    collaborators: &Vec<(Address, u32)>,
+    pinned: &Vec<Address>,
+) -> Result<Vec<(Address, u32)>, Error> {
+    let mut pinned_bp: u32 = 0;
+    let mut free_bp: u32 = 0;
+    let mut last_free = None;
+    for (i, (collab, bp)) in collaborators.iter().enumerate() {
+        if pinned.contains(&collab) {
+            pinned_bp += bp;
+        } else {
+            free_bp += bp;
+            last_free = Some(i as u32);
+        }
+    }
+
+    if pinned_bp > 10000 {
+        return Err(Error::TotalNot10000);
+    }
+    let target = 10000 - pinned_bp;
+    let last_free = match last_free {
+        Some(idx) => idx,
+        // Nothing left to scale; the pinned shares must add up on their own
+        None => return Ok(collaborators.clone()),
+    };
+
+    let mut result = Vec::new(env);
+    let mut assigned: u32 = 0;
+    for (i, (collab, bp)) in collaborators.iter().enumerate() {
+        let new_bp = if pinned.contains(&collab) {
+            bp
+        } else if i as u32 == last_free {
+            target - assigned
+        } else {
+            let scaled = ((bp as u64) * (target as u64) / (free_bp as u64)) as u32;
+            assigned += scaled;
+            scaled
+        };
+        if new_bp == 0 {
+            // A collaborator scaled down to nothing cannot stay in the split
+            return Err(Error::InvalidPercentage);
+        }
+        result.push_back((collab, new_bp));
+    }
+
+    Ok(result)
+}
+
+/// Collaborators whose share differs between `old` and `new`, as
+/// (address, old bp, new bp) with 0 standing for absent.
+fn split_diff(
+    env: &Env,
+    old: &Vec<(Address, u32)>,
+    new: &Vec<(Address, u32)>,
+) -> Vec<(Address, u32, u32)> {
+    let mut diff = Vec::new(env);
+    for (collab, old_bp) in old.iter() {
+        let new_bp = find_collaborator(new, &collab)
+            .map(|i| new.get(i).unwrap().1)
+            .unwrap_or(0);
+        if new_bp != old_bp {
+            diff.push_back((collab, old_bp, new_bp));
+        }
+    }
+    for (collab, new_bp) in new.iter() {
+        if find_collaborator(old, &collab).is_none() {
+            diff.push_back((collab, 0, new_bp));
+        }
+    }
+    diff
+}
+
+/// Work out each collaborator's share of `amount` for a track.
+fn compute_shares(env: &Env, track_id: &String, amount: i128) -> Result<Distribution, Error> {
+    let split: Vec<(Address, u32)> = env
+        .storage()
+        .persistent()
+        .get(&DataKey::Split(track_id.clone()))
+        .ok_or(Error::TrackNotFound)?;
+
+    let mut distributions = Vec::new(env);
+    let mut total_distributed = 0;
+    let collaborators_count = split.len();
+
+    for i in 0..collaborators_count {
+        let (collab, bp) = split.get(i).unwrap();
+
+        let share = if i == (collaborators_count - 1) {
+            // Last collaborator gets the remainder to handle rounding errors
+            amount - total_distributed
+        } else {
+            (amount * (bp as i128)) / 10000
+        };
+
+        total_distributed += share;
+        distributions.push_back((collab, share));
+    }
+
+    Ok(Distribution {
+        version: get_version_count(env, track_id),
+        shares: distributions,
+    })
+}
+
 mod test;
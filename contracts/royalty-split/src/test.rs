@@ -1,39 +1,48 @@
 #![cfg(test)]
 
+extern crate std;
+
 use super::*;
-use soroban_sdk::{testutils::Address as _, Address, Env, String, Vec};
+use soroban_sdk::{
+    testutils::{Address as _, Events, Ledger, MockAuth, MockAuthInvoke},
+    token, Address, Env, IntoVal, String, Symbol, TryFromVal, Vec,
+};
 
 #[test]
 fn test_set_and_distribute() {
     let env = Env::default();
+    env.mock_all_auths();
     let contract_id = env.register_contract(None, RoyaltySplit);
     let client = RoyaltySplitClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
 
     let track_id = String::from_str(&env, "track1");
     let user1 = Address::generate(&env);
     let user2 = Address::generate(&env);
 
     let mut collaborators = Vec::new(&env);
-    collaborators.push_back((user1.clone(), 6000));
+    collaborators.push_back((user1.clone(), 6000_u32));
     collaborators.push_back((user2.clone(), 4000));
 
     // Set split
-    client.set_royalty_split(&track_id, &collaborators);
+    client.set_royalty_split(&owner, &track_id, &collaborators);
 
     // Distribute
     let amount = 1000;
     let distributions = client.distribute_royalties(&track_id, &amount);
 
-    assert_eq!(distributions.len(), 2);
-    assert_eq!(distributions.get(0).unwrap(), (user1.clone(), 600));
-    assert_eq!(distributions.get(1).unwrap(), (user2.clone(), 400));
+    assert_eq!(distributions.shares.len(), 2);
+    assert_eq!(distributions.shares.get(0).unwrap(), (user1.clone(), 600));
+    assert_eq!(distributions.shares.get(1).unwrap(), (user2.clone(), 400));
 }
 
 #[test]
 fn test_rounding_behavior() {
     let env = Env::default();
+    env.mock_all_auths();
     let contract_id = env.register_contract(None, RoyaltySplit);
     let client = RoyaltySplitClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
 
     let track_id = String::from_str(&env, "track_round");
     let user1 = Address::generate(&env);
@@ -46,7 +55,7 @@ fn test_rounding_behavior() {
     collaborators.push_back((user2.clone(), 3333));
     collaborators.push_back((user3.clone(), 3334));
 
-    client.set_royalty_split(&track_id, &collaborators);
+    client.set_royalty_split(&owner, &track_id, &collaborators);
 
     // Distribute 100 units
     let amount = 100;
@@ -55,16 +64,18 @@ fn test_rounding_behavior() {
     // 100 * 3333 / 10000 = 33.33 -> 33
     // 100 * 3333 / 10000 = 33.33 -> 33
     // Remainder: 100 - 33 - 33 = 34
-    assert_eq!(distributions.get(0).unwrap().1, 33);
-    assert_eq!(distributions.get(1).unwrap().1, 33);
-    assert_eq!(distributions.get(2).unwrap().1, 34);
+    assert_eq!(distributions.shares.get(0).unwrap().1, 33);
+    assert_eq!(distributions.shares.get(1).unwrap().1, 33);
+    assert_eq!(distributions.shares.get(2).unwrap().1, 34);
 }
 
 #[test]
 fn test_total_not_10000() {
     let env = Env::default();
+    env.mock_all_auths();
     let contract_id = env.register_contract(None, RoyaltySplit);
     let client = RoyaltySplitClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
 
     let track_id = String::from_str(&env, "track2");
     let user1 = Address::generate(&env);
@@ -73,13 +84,14 @@ fn test_total_not_10000() {
     collaborators.push_back((user1.clone(), 9000));
 
     // Set split should fail with TotalNot10000
-    let res = client.try_set_royalty_split(&track_id, &collaborators);
+    let res = client.try_set_royalty_split(&owner, &track_id, &collaborators);
     assert_eq!(res, Err(Ok(Error::TotalNot10000)));
 }
 
 #[test]
 fn test_track_not_found() {
     let env = Env::default();
+    env.mock_all_auths();
     let contract_id = env.register_contract(None, RoyaltySplit);
     let client = RoyaltySplitClient::new(&env, &contract_id);
 
@@ -88,3 +100,403 @@ fn test_track_not_found() {
     let res = client.try_distribute_royalties(&track_id, &1000);
     assert_eq!(res, Err(Ok(Error::TrackNotFound)));
 }
+
+#[test]
+fn test_distribute_and_pay() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, RoyaltySplit);
+    let client = RoyaltySplitClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+
+    let token_admin = Address::generate(&env);
+    let token_address = env
+        .register_stellar_asset_contract_v2(token_admin)
+        .address();
+    let token = token::Client::new(&env, &token_address);
+    let payer = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &token_address).mint(&payer, &1000);
+
+    let track_id = String::from_str(&env, "track_pay");
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let user3 = Address::generate(&env);
+
+    let mut collaborators = Vec::new(&env);
+    collaborators.push_back((user1.clone(), 3333));
+    collaborators.push_back((user2.clone(), 3333));
+    collaborators.push_back((user3.clone(), 3334));
+    client.set_royalty_split(&owner, &track_id, &collaborators);
+
+    let distributions = client.distribute_and_pay(&payer, &track_id, &token_address, &100);
+
+    // One payout event per recipient
+    let payouts = env
+        .events()
+        .all()
+        .iter()
+        .filter(|event| {
+            event.0 == contract_id
+                && Symbol::try_from_val(&env, &event.1.get(1).unwrap())
+                    == Ok(symbol_short!("payout"))
+        })
+        .count();
+    assert_eq!(payouts, 3);
+
+    assert_eq!(distributions, client.distribute_royalties(&track_id, &100));
+    assert_eq!(token.balance(&user1), 33);
+    assert_eq!(token.balance(&user2), 33);
+    assert_eq!(token.balance(&user3), 34);
+    assert_eq!(token.balance(&payer), 900);
+
+}
+
+#[test]
+fn test_distribute_and_pay_invalid_amount() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, RoyaltySplit);
+    let client = RoyaltySplitClient::new(&env, &contract_id);
+
+    let track_id = String::from_str(&env, "track_zero");
+    let payer = Address::generate(&env);
+    let token = Address::generate(&env);
+
+    let res = client.try_distribute_and_pay(&payer, &track_id, &token, &0);
+    assert_eq!(res, Err(Ok(Error::InvalidAmount)));
+}
+
+fn setup_split(
+    env: &Env,
+    client: &RoyaltySplitClient,
+    owner: &Address,
+    track_id: &String,
+) -> (Address, Address, Address) {
+    let user1 = Address::generate(env);
+    let user2 = Address::generate(env);
+    let user3 = Address::generate(env);
+
+    let mut collaborators = Vec::new(env);
+    collaborators.push_back((user1.clone(), 5000));
+    collaborators.push_back((user2.clone(), 3000));
+    collaborators.push_back((user3.clone(), 2000));
+    client.set_royalty_split(owner, track_id, &collaborators);
+
+    (user1, user2, user3)
+}
+
+#[test]
+fn test_update_shares_rebalance() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, RoyaltySplit);
+    let client = RoyaltySplitClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+
+    let track_id = String::from_str(&env, "track_update");
+    let (user1, user2, user3) = setup_split(&env, &client, &owner, &track_id);
+    let user4 = Address::generate(&env);
+
+    // Add a 2000 bp collaborator; the others shrink to fill the remaining 8000
+    let mut changes = Vec::new(&env);
+    changes.push_back(ShareChange::Add(user4.clone(), 2000));
+    let split = client.update_shares(&owner, &track_id, &changes, &UpdateMode::Rebalance);
+
+    assert_eq!(split.get(0).unwrap(), (user1.clone(), 4000));
+    assert_eq!(split.get(1).unwrap(), (user2.clone(), 2400));
+    assert_eq!(split.get(2).unwrap(), (user3.clone(), 1600));
+    assert_eq!(split.get(3).unwrap(), (user4.clone(), 2000));
+
+    // Remove one and re-weight another in the same update
+    let mut changes = Vec::new(&env);
+    changes.push_back(ShareChange::Remove(user2.clone()));
+    changes.push_back(ShareChange::Reweight(user4.clone(), 5000));
+    let split = client.update_shares(&owner, &track_id, &changes, &UpdateMode::Rebalance);
+
+    // 4000:1600 scaled into the remaining 5000 bp
+    assert_eq!(split.len(), 3);
+    assert_eq!(split.get(0).unwrap(), (user1.clone(), 3571));
+    assert_eq!(split.get(1).unwrap(), (user3.clone(), 1429));
+    assert_eq!(split.get(2).unwrap(), (user4.clone(), 5000));
+}
+
+#[test]
+fn test_update_shares_exact() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, RoyaltySplit);
+    let client = RoyaltySplitClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+
+    let track_id = String::from_str(&env, "track_exact");
+    let (user1, user2, _) = setup_split(&env, &client, &owner, &track_id);
+
+    // Moving 1000 bp from user1 to user2 keeps the total at 10,000
+    let mut changes = Vec::new(&env);
+    changes.push_back(ShareChange::Reweight(user1.clone(), 4000));
+    changes.push_back(ShareChange::Reweight(user2.clone(), 4000));
+    let split = client.update_shares(&owner, &track_id, &changes, &UpdateMode::Exact);
+    assert_eq!(split.get(0).unwrap(), (user1.clone(), 4000));
+    assert_eq!(split.get(1).unwrap(), (user2.clone(), 4000));
+
+    // A change that breaks the total is rejected and nothing is stored
+    let mut changes = Vec::new(&env);
+    changes.push_back(ShareChange::Reweight(user1.clone(), 3000));
+    let res = client.try_update_shares(&owner, &track_id, &changes, &UpdateMode::Exact);
+    assert_eq!(res, Err(Ok(Error::TotalNot10000)));
+    assert_eq!(
+        client.distribute_royalties(&track_id, &1000).shares.get(0).unwrap(),
+        (user1, 400)
+    );
+}
+
+#[test]
+fn test_update_shares_errors() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, RoyaltySplit);
+    let client = RoyaltySplitClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+
+    let track_id = String::from_str(&env, "track_errors");
+    let (user1, _, _) = setup_split(&env, &client, &owner, &track_id);
+    let stranger = Address::generate(&env);
+
+    let mut changes = Vec::new(&env);
+    changes.push_back(ShareChange::Add(user1.clone(), 1000));
+    let res = client.try_update_shares(&owner, &track_id, &changes, &UpdateMode::Rebalance);
+    assert_eq!(res, Err(Ok(Error::AlreadyExists)));
+
+    let mut changes = Vec::new(&env);
+    changes.push_back(ShareChange::Remove(stranger.clone()));
+    let res = client.try_update_shares(&owner, &track_id, &changes, &UpdateMode::Rebalance);
+    assert_eq!(res, Err(Ok(Error::CollaboratorNotFound)));
+
+    let mut changes = Vec::new(&env);
+    changes.push_back(ShareChange::Reweight(user1.clone(), 0));
+    let res = client.try_update_shares(&owner, &track_id, &changes, &UpdateMode::Rebalance);
+    assert_eq!(res, Err(Ok(Error::InvalidPercentage)));
+}
+
+#[test]
+fn test_remove_collaborator_rebalances() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, RoyaltySplit);
+    let client = RoyaltySplitClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+
+    let track_id = String::from_str(&env, "track_remove");
+    let (user1, user2, user3) = setup_split(&env, &client, &owner, &track_id);
+
+    client.remove_collaborator(&owner, &track_id, &user3);
+
+    let distributions = client.distribute_royalties(&track_id, &1000);
+    assert_eq!(distributions.shares.len(), 2);
+    assert_eq!(distributions.shares.get(0).unwrap(), (user1, 625));
+    assert_eq!(distributions.shares.get(1).unwrap(), (user2, 375));
+}
+
+#[test]
+fn test_only_owner_can_change_split() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, RoyaltySplit);
+    let client = RoyaltySplitClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    let attacker = Address::generate(&env);
+
+    let track_id = String::from_str(&env, "track_owned");
+    let (user1, _, _) = setup_split(&env, &client, &owner, &track_id);
+    assert_eq!(client.get_owner(&track_id), Some(owner.clone()));
+
+    let mut hijack = Vec::new(&env);
+    hijack.push_back((attacker.clone(), 10000));
+    let res = client.try_set_royalty_split(&attacker, &track_id, &hijack);
+    assert_eq!(res, Err(Ok(Error::Unauthorized)));
+
+    let mut changes = Vec::new(&env);
+    changes.push_back(ShareChange::Remove(user1.clone()));
+    let res = client.try_update_shares(&attacker, &track_id, &changes, &UpdateMode::Rebalance);
+    assert_eq!(res, Err(Ok(Error::Unauthorized)));
+    let res = client.try_remove_collaborator(&attacker, &track_id, &user1);
+    assert_eq!(res, Err(Ok(Error::Unauthorized)));
+}
+
+#[test]
+fn test_transfer_ownership() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, RoyaltySplit);
+    let client = RoyaltySplitClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    let new_owner = Address::generate(&env);
+
+    let track_id = String::from_str(&env, "track_transfer");
+    let (user1, _, user3) = setup_split(&env, &client, &owner, &track_id);
+
+    client.transfer_ownership(&owner, &track_id, &new_owner);
+    assert_eq!(client.get_owner(&track_id), Some(new_owner.clone()));
+
+    let res = client.try_remove_collaborator(&owner, &track_id, &user1);
+    assert_eq!(res, Err(Ok(Error::Unauthorized)));
+    client.remove_collaborator(&new_owner, &track_id, &user3);
+
+    let res = client.try_transfer_ownership(&owner, &track_id, &owner);
+    assert_eq!(res, Err(Ok(Error::Unauthorized)));
+}
+
+#[test]
+fn test_collaborators_assign_owner_to_legacy_track() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, RoyaltySplit);
+    let client = RoyaltySplitClient::new(&env, &contract_id);
+    let attacker = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+
+    // A split stored before tracks recorded an owner
+    let track_id = String::from_str(&env, "track_legacy");
+    let mut collaborators = Vec::new(&env);
+    collaborators.push_back((user1.clone(), 6000_u32));
+    collaborators.push_back((user2.clone(), 4000));
+    env.as_contract(&contract_id, || {
+        env.storage()
+            .persistent()
+            .set(&DataKey::Split(track_id.clone()), &collaborators);
+    });
+
+    // The first caller does not get to take it over
+    let mut hijack = Vec::new(&env);
+    hijack.push_back((attacker.clone(), 10000));
+    let res = client.try_set_royalty_split(&attacker, &track_id, &hijack);
+    assert_eq!(res, Err(Ok(Error::Unauthorized)));
+    assert_eq!(client.get_owner(&track_id), None);
+
+    // The attacker alone cannot claim it either
+    env.mock_auths(&[MockAuth {
+        address: &attacker,
+        invoke: &MockAuthInvoke {
+            contract: &contract_id,
+            fn_name: "claim_ownership",
+            args: (track_id.clone(), attacker.clone()).into_val(&env),
+            sub_invokes: &[],
+        },
+    }]);
+    assert!(client.try_claim_ownership(&track_id, &attacker).is_err());
+
+    env.mock_all_auths();
+    client.claim_ownership(&track_id, &user1);
+    let signers: std::vec::Vec<Address> = env.auths().into_iter().map(|(a, _)| a).collect();
+    assert_eq!(signers, std::vec![user1.clone(), user2.clone()]);
+    assert_eq!(client.get_owner(&track_id), Some(user1.clone()));
+
+    let res = client.try_claim_ownership(&track_id, &attacker);
+    assert_eq!(res, Err(Ok(Error::AlreadyExists)));
+    let res = client.try_claim_ownership(&String::from_str(&env, "track_missing"), &attacker);
+    assert_eq!(res, Err(Ok(Error::TrackNotFound)));
+}
+
+#[test]
+fn test_consent_required_for_share_reduction() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, RoyaltySplit);
+    let client = RoyaltySplitClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+
+    let track_id = String::from_str(&env, "track_consent");
+    let (user1, user2, user3) = setup_split(&env, &client, &owner, &track_id);
+
+    // Without consent mode only the owner signs
+    let mut changes = Vec::new(&env);
+    changes.push_back(ShareChange::Reweight(user1.clone(), 4000));
+    changes.push_back(ShareChange::Reweight(user2.clone(), 4000));
+    client.update_shares(&owner, &track_id, &changes, &UpdateMode::Exact);
+    let signers: std::vec::Vec<Address> = env.auths().into_iter().map(|(a, _)| a).collect();
+    assert_eq!(signers, std::vec![owner.clone()]);
+
+    client.set_consent_required(&owner, &track_id, &true);
+
+    // user1 loses 1000 bp to user3, so user1 must co-sign; user3 gains and need not
+    let mut changes = Vec::new(&env);
+    changes.push_back(ShareChange::Reweight(user1.clone(), 3000));
+    changes.push_back(ShareChange::Reweight(user3.clone(), 3000));
+    client.update_shares(&owner, &track_id, &changes, &UpdateMode::Exact);
+    let signers: std::vec::Vec<Address> = env.auths().into_iter().map(|(a, _)| a).collect();
+    assert_eq!(signers, std::vec![owner.clone(), user1.clone()]);
+}
+
+#[test]
+fn test_disabling_consent_needs_collaborators() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, RoyaltySplit);
+    let client = RoyaltySplitClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+
+    let track_id = String::from_str(&env, "track_consent_off");
+    let (user1, user2, user3) = setup_split(&env, &client, &owner, &track_id);
+
+    client.set_consent_required(&owner, &track_id, &true);
+    let signers: std::vec::Vec<Address> = env.auths().into_iter().map(|(a, _)| a).collect();
+    assert_eq!(signers, std::vec![owner.clone()]);
+
+    client.set_consent_required(&owner, &track_id, &false);
+    let signers: std::vec::Vec<Address> = env.auths().into_iter().map(|(a, _)| a).collect();
+    assert_eq!(signers, std::vec![owner.clone(), user1, user2, user3]);
+
+    // The owner alone cannot switch consent off
+    client.set_consent_required(&owner, &track_id, &true);
+    env.mock_auths(&[MockAuth {
+        address: &owner,
+        invoke: &MockAuthInvoke {
+            contract: &contract_id,
+            fn_name: "set_consent_required",
+            args: (owner.clone(), track_id.clone(), false).into_val(&env),
+            sub_invokes: &[],
+        },
+    }]);
+    assert!(client.try_set_consent_required(&owner, &track_id, &false).is_err());
+}
+
+#[test]
+fn test_split_versions() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, RoyaltySplit);
+    let client = RoyaltySplitClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+
+    env.ledger().set_timestamp(1000);
+    let track_id = String::from_str(&env, "track_versions");
+    let (user1, user2, user3) = setup_split(&env, &client, &owner, &track_id);
+    assert_eq!(client.distribute_royalties(&track_id, &1000).version, 1);
+
+    env.ledger().set_timestamp(2000);
+    client.remove_collaborator(&owner, &track_id, &user3);
+
+    let distribution = client.distribute_royalties(&track_id, &1000);
+    assert_eq!(distribution.version, 2);
+    assert_eq!(distribution.shares.len(), 2);
+
+    let versions = client.get_split_versions(&track_id);
+    assert_eq!(versions.len(), 2);
+    assert_eq!(versions.get(0).unwrap().effective_from, 1000);
+    assert_eq!(versions.get(1).unwrap().effective_from, 2000);
+
+    let at_start = client.get_split_at(&track_id, &1500);
+    assert_eq!(at_start.version, 1);
+    assert_eq!(at_start.collaborators.get(2).unwrap(), (user3, 2000));
+
+    let later = client.get_split_at(&track_id, &2000);
+    assert_eq!(later.version, 2);
+    assert_eq!(later.collaborators.get(0).unwrap(), (user1, 6250));
+    assert_eq!(later.collaborators.get(1).unwrap(), (user2, 3750));
+
+    let res = client.try_get_split_at(&track_id, &999);
+    assert_eq!(res, Err(Ok(Error::VersionNotFound)));
+    let res = client.try_get_split_at(&String::from_str(&env, "unknown"), &1500);
+    assert_eq!(res, Err(Ok(Error::TrackNotFound)));
+}
@@ -19,4 +19,11 @@ pub enum Error {
     AmountBelowMinimum    = 13,
     AmountAboveMaximum    = 14,
     FeeTooHigh            = 15,
+    BridgeAlreadyExists   = 16,
+    BridgeNotExpired      = 17,
+    InvalidOracleSet      = 18,
+    ChainPaused           = 19,
+    DailyCapExceeded      = 20,
+    BridgePaused          = 21,
+    RecipientCapExceeded  = 22,
 }
\ No newline at end of file
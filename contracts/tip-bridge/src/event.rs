@@ -1,4 +1,4 @@
-use soroban_sdk::{Address, Bytes, Env, String, symbol_short};
+use soroban_sdk::{Address, Env, String, symbol_short};
 
 pub fn bridge_initiated(
     env: &Env,
@@ -10,7 +10,7 @@ pub fn bridge_initiated(
 ) {
     env.events().publish(
         (symbol_short!("BRIDGE"), symbol_short!("INIT")),
-        (bridge_id, source_chain, recipient, amount, fee),
+        (bridge_id.clone(), source_chain.clone(), recipient.clone(), amount, fee),
     );
 }
 
@@ -22,7 +22,21 @@ pub fn bridge_completed(
 ) {
     env.events().publish(
         (symbol_short!("BRIDGE"), symbol_short!("DONE")),
-        (bridge_id, recipient, net_amount),
+        (bridge_id.clone(), recipient.clone(), net_amount),
+    );
+}
+
+pub fn relayer_updated(env: &Env, relayer: &Address, allowed: bool) {
+    env.events().publish(
+        (symbol_short!("BRIDGE"), symbol_short!("RELAYER")),
+        (relayer.clone(), allowed),
+    );
+}
+
+pub fn bridge_cancelled(env: &Env, bridge_id: &String, recipient: &Address) {
+    env.events().publish(
+        (symbol_short!("BRIDGE"), symbol_short!("CANCEL")),
+        (bridge_id.clone(), recipient.clone()),
     );
 }
 
@@ -36,13 +50,62 @@ pub fn bridge_back_initiated(
 ) {
     env.events().publish(
         (symbol_short!("BBACK"), symbol_short!("INIT")),
-        (bridge_id, destination_chain, sender, amount, fee),
+        (bridge_id.clone(), destination_chain.clone(), sender.clone(), amount, fee),
     );
 }
 
 pub fn fees_collected(env: &Env, collector: &Address, amount: i128) {
     env.events().publish(
         (symbol_short!("FEE"), symbol_short!("COLLECT")),
-        (collector, amount),
+        (collector.clone(), amount),
+    );
+}
+
+pub fn oracle_set_updated(env: &Env, signer_count: u32, threshold: u32) {
+    env.events().publish(
+        (symbol_short!("ORACLE"), symbol_short!("UPDATE")),
+        (signer_count, threshold),
+    );
+}
+
+pub fn chain_updated(env: &Env, chain: &String, enabled: bool) {
+    env.events().publish(
+        (symbol_short!("CHAIN"), symbol_short!("UPDATE")),
+        (chain.clone(), enabled),
+    );
+}
+
+pub fn chain_removed(env: &Env, chain: &String) {
+    env.events().publish(
+        (symbol_short!("CHAIN"), symbol_short!("REMOVE")),
+        (chain.clone(),),
+    );
+}
+
+pub fn bridge_paused(env: &Env, by: &Address) {
+    env.events().publish(
+        (symbol_short!("BRIDGE"), symbol_short!("PAUSE")),
+        (by.clone(),),
+    );
+}
+
+pub fn bridge_unpaused(env: &Env, by: &Address) {
+    env.events().publish(
+        (symbol_short!("BRIDGE"), symbol_short!("UNPAUSE")),
+        (by.clone(),),
     );
-}
\ No newline at end of file
+}
+
+/// Emitted when an inbound transfer would push the window total over its cap.
+pub fn circuit_breaker_tripped(
+    env: &Env,
+    bridge_id: &String,
+    window_total: i128,
+    attempted_amount: i128,
+    window_cap: i128,
+) {
+    env.events().publish(
+        (symbol_short!("BRIDGE"), symbol_short!("BREAKER")),
+        (bridge_id.clone(), window_total, attempted_amount, window_cap),
+    );
+}
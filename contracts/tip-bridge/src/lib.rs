@@ -1,14 +1,608 @@
-pub fn add(left: u64, right: u64) -> u64 {
-    left + right
+#![no_std]
+
+mod errors;
+mod event;
+mod storage;
+mod types;
+
+use soroban_sdk::{
+    contract, contractimpl, token, xdr::ToXdr, Address, Bytes, BytesN, Env, Map, String, Vec,
+};
+
+pub use errors::Error;
+pub use types::{
+    BridgeBackRecord, BridgeConfig, BridgeRecord, BridgeStatus, ChainConfig, InflowWindow,
+    OracleSet, RateLimitConfig,
+};
+
+const MAX_FEE_BASIS_POINTS: u32 = 1_000; // 10%
+const BASIS_POINTS: i128 = 10_000;
+// An attestation is the signer's public key followed by its signature.
+const ATTESTATION_LEN: u32 = 32 + 64;
+// Bumped whenever the layout of the attested payload changes.
+const ATTESTATION_VERSION: u32 = 1;
+
+#[contract]
+pub struct TipBridgeContract;
+
+#[contractimpl]
+impl TipBridgeContract {
+    /// Configure the bridge. The contract must be (or become) the admin of
+    /// `wrapped_token` so that it can mint inbound tips.
+    #[allow(clippy::too_many_arguments)]
+    pub fn initialize(
+        env: Env,
+        admin: Address,
+        oracle: Address,
+        wrapped_token: Address,
+        fee_collector: Address,
+        fee_basis_points: u32,
+        min_amount: i128,
+        max_amount: i128,
+        bridge_ttl_ledgers: u32,
+    ) -> Result<(), Error> {
+        if storage::get_config(&env).is_some() {
+            return Err(Error::AlreadyInitialized);
+        }
+        admin.require_auth();
+
+        if fee_basis_points > MAX_FEE_BASIS_POINTS {
+            return Err(Error::FeeTooHigh);
+        }
+        if min_amount <= 0 || max_amount < min_amount {
+            return Err(Error::InvalidAmount);
+        }
+
+        let config = BridgeConfig {
+            admin,
+            oracle,
+            wrapped_token,
+            fee_collector,
+            fee_basis_points,
+            min_amount,
+            max_amount,
+            bridge_ttl_ledgers,
+            supported_chains: Map::new(&env),
+        };
+        storage::set_config(&env, &config);
+        Ok(())
+    }
+
+    /// Register a chain or replace its settings.
+    pub fn set_chain(env: Env, chain: String, chain_config: ChainConfig) -> Result<(), Error> {
+        let mut config = storage::get_config(&env).ok_or(Error::NotInitialized)?;
+        config.admin.require_auth();
+
+        if chain_config.min_amount <= 0
+            || chain_config.max_amount < chain_config.min_amount
+            || chain_config.daily_inflow_cap < 0
+        {
+            return Err(Error::InvalidAmount);
+        }
+        if let Some(fee_basis_points) = chain_config.fee_basis_points {
+            if fee_basis_points > MAX_FEE_BASIS_POINTS {
+                return Err(Error::FeeTooHigh);
+            }
+        }
+
+        let enabled = chain_config.enabled;
+        config.supported_chains.set(chain.clone(), chain_config);
+        storage::set_config(&env, &config);
+
+        event::chain_updated(&env, &chain, enabled);
+        Ok(())
+    }
+
+    /// Pause or resume bridging to and from a registered chain.
+    pub fn set_chain_enabled(env: Env, chain: String, enabled: bool) -> Result<(), Error> {
+        let mut config = storage::get_config(&env).ok_or(Error::NotInitialized)?;
+        config.admin.require_auth();
+
+        let mut chain_config = config
+            .supported_chains
+            .get(chain.clone())
+            .ok_or(Error::UnsupportedChain)?;
+        chain_config.enabled = enabled;
+        config.supported_chains.set(chain.clone(), chain_config);
+        storage::set_config(&env, &config);
+
+        event::chain_updated(&env, &chain, enabled);
+        Ok(())
+    }
+
+    pub fn remove_chain(env: Env, chain: String) -> Result<(), Error> {
+        let mut config = storage::get_config(&env).ok_or(Error::NotInitialized)?;
+        config.admin.require_auth();
+
+        if config.supported_chains.remove(chain.clone()).is_none() {
+            return Err(Error::UnsupportedChain);
+        }
+        storage::set_config(&env, &config);
+
+        event::chain_removed(&env, &chain);
+        Ok(())
+    }
+
+    /// Configure the rolling inflow limits. Exceeding `window_inflow_cap` trips
+    /// the circuit breaker; exceeding `recipient_inflow_cap` rejects the transfer.
+    pub fn set_rate_limits(env: Env, limits: RateLimitConfig) -> Result<(), Error> {
+        let config = storage::get_config(&env).ok_or(Error::NotInitialized)?;
+        config.admin.require_auth();
+
+        if limits.window_ledgers == 0
+            || limits.window_inflow_cap < 0
+            || limits.recipient_inflow_cap < 0
+        {
+            return Err(Error::InvalidAmount);
+        }
+        storage::set_rate_limits(&env, &limits);
+        Ok(())
+    }
+
+    /// Allow or revoke a relayer. Only registered relayers may register
+    /// inbound transfers.
+    pub fn set_relayer(env: Env, relayer: Address, allowed: bool) -> Result<(), Error> {
+        let config = storage::get_config(&env).ok_or(Error::NotInitialized)?;
+        config.admin.require_auth();
+
+        storage::set_relayer(&env, &relayer, allowed);
+
+        event::relayer_updated(&env, &relayer, allowed);
+        Ok(())
+    }
+
+    /// Set the guardian allowed to pause (but not unpause) the bridge.
+    pub fn set_guardian(env: Env, guardian: Address) -> Result<(), Error> {
+        let config = storage::get_config(&env).ok_or(Error::NotInitialized)?;
+        config.admin.require_auth();
+
+        storage::set_guardian(&env, &guardian);
+        Ok(())
+    }
+
+    /// Circuit breaker: halt all inbound and outbound bridging. Callable by the
+    /// admin or the guardian.
+    pub fn pause_bridge(env: Env, caller: Address) -> Result<(), Error> {
+        let config = storage::get_config(&env).ok_or(Error::NotInitialized)?;
+        caller.require_auth();
+
+        if caller != config.admin && Some(caller.clone()) != storage::get_guardian(&env) {
+            return Err(Error::Unauthorized);
+        }
+        storage::set_paused(&env, true);
+
+        event::bridge_paused(&env, &caller);
+        Ok(())
+    }
+
+    pub fn unpause_bridge(env: Env) -> Result<(), Error> {
+        let config = storage::get_config(&env).ok_or(Error::NotInitialized)?;
+        config.admin.require_auth();
+
+        storage::set_paused(&env, false);
+
+        event::bridge_unpaused(&env, &config.admin);
+        Ok(())
+    }
+
+    /// Replace the ed25519 oracle keys that attest inbound transfers.
+    /// Completions require `threshold` signatures from these keys, so nothing
+    /// can be minted until a set is configured.
+    pub fn set_oracle_signers(
+        env: Env,
+        signers: Vec<BytesN<32>>,
+        threshold: u32,
+    ) -> Result<(), Error> {
+        let config = storage::get_config(&env).ok_or(Error::NotInitialized)?;
+        config.admin.require_auth();
+
+        if threshold == 0 || threshold > signers.len() {
+            return Err(Error::InvalidOracleSet);
+        }
+        for (i, signer) in signers.iter().enumerate() {
+            if signers.first_index_of(&signer) != Some(i as u32) {
+                return Err(Error::InvalidOracleSet);
+            }
+        }
+
+        storage::set_oracle_set(&env, &OracleSet { signers: signers.clone(), threshold });
+
+        event::oracle_set_updated(&env, signers.len(), threshold);
+        Ok(())
+    }
+
+    /// Register an inbound transfer observed on `source_chain`. Only relayers
+    /// registered by the admin may call this. The record stays `Pending` until
+    /// the oracle attests it through `complete_bridge`, and can be cancelled
+    /// once `bridge_ttl_ledgers` have passed, after which its id may be
+    /// registered again.
+    #[allow(clippy::too_many_arguments)]
+    pub fn initiate_bridge(
+        env: Env,
+        relayer: Address,
+        bridge_id: String,
+        source_chain: String,
+        sender: Bytes,
+        recipient: Address,
+        amount: i128,
+    ) -> Result<(), Error> {
+        relayer.require_auth();
+        let config = storage::get_config(&env).ok_or(Error::NotInitialized)?;
+        if !storage::is_relayer(&env, &relayer) {
+            return Err(Error::Unauthorized);
+        }
+        if storage::is_paused(&env) {
+            return Err(Error::BridgePaused);
+        }
+
+        if let Some(existing) = storage::get_bridge(&env, &bridge_id) {
+            if existing.status != BridgeStatus::Cancelled {
+                return Err(Error::BridgeAlreadyExists);
+            }
+        }
+        let chain_config = enabled_chain(&config, &source_chain)?;
+        let fee = validate_amount(&config, &chain_config, amount)?;
+
+        let current_ledger = env.ledger().sequence() as u64;
+        let record = BridgeRecord {
+            bridge_id: bridge_id.clone(),
+            source_chain: source_chain.clone(),
+            sender,
+            recipient: recipient.clone(),
+            amount,
+            fee,
+            net_amount: amount - fee,
+            initiated_at: env.ledger().timestamp(),
+            expires_at: current_ledger + config.bridge_ttl_ledgers as u64,
+            status: BridgeStatus::Pending,
+            proof: Vec::new(&env),
+        };
+        storage::set_bridge(&env, &bridge_id, &record);
+
+        event::bridge_initiated(&env, &bridge_id, &source_chain, &recipient, amount, fee);
+        Ok(())
+    }
+
+    /// Oracle-attested completion of a pending inbound transfer: mints the net
+    /// amount of `wrapped_token` to the recipient and accrues the fee.
+    ///
+    /// `proof` must carry attestations (`public_key || signature`) from at
+    /// least `threshold` oracle keys over `attestation_payload`. Fails with
+    /// `InvalidOracleSet` while no oracle set is configured.
+    ///
+    /// If the transfer would exceed the window inflow cap, the circuit breaker
+    /// trips instead: the bridge is paused, the record stays `Pending` and `0`
+    /// is returned. This is not an error so that the pause is persisted.
+    pub fn complete_bridge(env: Env, bridge_id: String, proof: Vec<Bytes>) -> Result<i128, Error> {
+        let config = storage::get_config(&env).ok_or(Error::NotInitialized)?;
+        if storage::is_paused(&env) {
+            return Err(Error::BridgePaused);
+        }
+
+        let mut record = storage::get_bridge(&env, &bridge_id).ok_or(Error::BridgeNotFound)?;
+        match record.status {
+            BridgeStatus::Completed => return Err(Error::AlreadyCompleted),
+            BridgeStatus::Cancelled => return Err(Error::AlreadyCancelled),
+            BridgeStatus::Pending => {}
+        }
+        if env.ledger().sequence() as u64 > record.expires_at {
+            return Err(Error::BridgeExpired);
+        }
+
+        let chain_config = enabled_chain(&config, &record.source_chain)?;
+        if chain_config.daily_inflow_cap > 0
+            && storage::get_daily_inflow(&env, &record.source_chain) + record.amount
+                > chain_config.daily_inflow_cap
+        {
+            return Err(Error::DailyCapExceeded);
+        }
+
+        let oracle_set = storage::get_oracle_set(&env).ok_or(Error::InvalidOracleSet)?;
+        verify_attestations(&env, &oracle_set, &record, &proof)?;
+        record.proof = proof;
+
+        if let Some(limits) = storage::get_rate_limits(&env) {
+            let current_ledger = env.ledger().sequence();
+            let window = current_window(storage::get_inflow_window(&env), &limits, current_ledger);
+            if limits.window_inflow_cap > 0
+                && window.total + record.amount > limits.window_inflow_cap
+            {
+                storage::set_paused(&env, true);
+                event::circuit_breaker_tripped(
+                    &env,
+                    &bridge_id,
+                    window.total,
+                    record.amount,
+                    limits.window_inflow_cap,
+                );
+                return Ok(0);
+            }
+
+            let recipient_window = current_window(
+                storage::get_recipient_window(&env, &record.recipient),
+                &limits,
+                current_ledger,
+            );
+            if limits.recipient_inflow_cap > 0
+                && recipient_window.total + record.amount > limits.recipient_inflow_cap
+            {
+                return Err(Error::RecipientCapExceeded);
+            }
+
+            storage::set_inflow_window(
+                &env,
+                &InflowWindow { total: window.total + record.amount, ..window },
+            );
+            storage::set_recipient_window(
+                &env,
+                &record.recipient,
+                &InflowWindow {
+                    total: recipient_window.total + record.amount,
+                    ..recipient_window
+                },
+            );
+        }
+
+        let token_admin = token::StellarAssetClient::new(&env, &config.wrapped_token);
+        token_admin.mint(&record.recipient, &record.net_amount);
+        if record.fee > 0 {
+            token_admin.mint(&env.current_contract_address(), &record.fee);
+            storage::add_fees(&env, record.fee);
+        }
+        storage::add_daily_inflow(&env, &record.source_chain, record.amount);
+
+        record.status = BridgeStatus::Completed;
+        storage::set_bridge(&env, &bridge_id, &record);
+
+        event::bridge_completed(&env, &bridge_id, &record.recipient, record.net_amount);
+        Ok(record.net_amount)
+    }
+
+    /// Cancel a pending inbound transfer whose TTL has elapsed. Callable by anyone.
+    pub fn cancel_expired_bridge(env: Env, bridge_id: String) -> Result<(), Error> {
+        let mut record = storage::get_bridge(&env, &bridge_id).ok_or(Error::BridgeNotFound)?;
+        match record.status {
+            BridgeStatus::Completed => return Err(Error::AlreadyCompleted),
+            BridgeStatus::Cancelled => return Err(Error::AlreadyCancelled),
+            BridgeStatus::Pending => {}
+        }
+        if env.ledger().sequence() as u64 <= record.expires_at {
+            return Err(Error::BridgeNotExpired);
+        }
+
+        record.status = BridgeStatus::Cancelled;
+        storage::set_bridge(&env, &bridge_id, &record);
+
+        event::bridge_cancelled(&env, &bridge_id, &record.recipient);
+        Ok(())
+    }
+
+    /// Send wrapped tips back to `destination_chain`. The full amount is pulled
+    /// from `sender`, the net amount is burned and the fee is kept for the
+    /// fee collector.
+    pub fn initiate_bridge_back(
+        env: Env,
+        sender: Address,
+        bridge_id: String,
+        destination_chain: String,
+        recipient: Bytes,
+        amount: i128,
+    ) -> Result<(), Error> {
+        sender.require_auth();
+        let config = storage::get_config(&env).ok_or(Error::NotInitialized)?;
+        if storage::is_paused(&env) {
+            return Err(Error::BridgePaused);
+        }
+
+        if storage::get_bridge_back(&env, &bridge_id).is_some() {
+            return Err(Error::BridgeAlreadyExists);
+        }
+        let chain_config = enabled_chain(&config, &destination_chain)?;
+        let fee = validate_amount(&config, &chain_config, amount)?;
+
+        let token_client = token::Client::new(&env, &config.wrapped_token);
+        if token_client.balance(&sender) < amount {
+            return Err(Error::InsufficientBalance);
+        }
+
+        let contract_address = env.current_contract_address();
+        let net_amount = amount - fee;
+        token_client.transfer(&sender, &contract_address, &amount);
+        token_client.burn(&contract_address, &net_amount);
+        if fee > 0 {
+            storage::add_fees(&env, fee);
+        }
+
+        let record = BridgeBackRecord {
+            bridge_id: bridge_id.clone(),
+            destination_chain: destination_chain.clone(),
+            sender: sender.clone(),
+            recipient,
+            amount,
+            fee,
+            net_amount,
+            initiated_at: env.ledger().timestamp(),
+            status: BridgeStatus::Completed,
+        };
+        storage::set_bridge_back(&env, &bridge_id, &record);
+
+        event::bridge_back_initiated(&env, &bridge_id, &destination_chain, &sender, amount, fee);
+        Ok(())
+    }
+
+    /// Transfer all accrued fees to the configured fee collector.
+    pub fn withdraw_fees(env: Env) -> Result<i128, Error> {
+        let config = storage::get_config(&env).ok_or(Error::NotInitialized)?;
+        config.fee_collector.require_auth();
+
+        let amount = storage::get_total_fees(&env);
+        if amount <= 0 {
+            return Err(Error::InsufficientBalance);
+        }
+
+        let token_client = token::Client::new(&env, &config.wrapped_token);
+        token_client.transfer(&env.current_contract_address(), &config.fee_collector, &amount);
+        storage::reset_fees(&env);
+
+        event::fees_collected(&env, &config.fee_collector, amount);
+        Ok(amount)
+    }
+
+    pub fn get_config(env: Env) -> Result<BridgeConfig, Error> {
+        storage::get_config(&env).ok_or(Error::NotInitialized)
+    }
+
+    pub fn get_bridge(env: Env, bridge_id: String) -> Result<BridgeRecord, Error> {
+        storage::get_bridge(&env, &bridge_id).ok_or(Error::BridgeNotFound)
+    }
+
+    pub fn get_bridge_back(env: Env, bridge_id: String) -> Result<BridgeBackRecord, Error> {
+        storage::get_bridge_back(&env, &bridge_id).ok_or(Error::BridgeNotFound)
+    }
+
+    pub fn get_total_fees(env: Env) -> i128 {
+        storage::get_total_fees(&env)
+    }
+
+    pub fn get_oracle_set(env: Env) -> Option<OracleSet> {
+        storage::get_oracle_set(&env)
+    }
+
+    pub fn get_chain(env: Env, chain: String) -> Result<ChainConfig, Error> {
+        let config = storage::get_config(&env).ok_or(Error::NotInitialized)?;
+        config.supported_chains.get(chain).ok_or(Error::UnsupportedChain)
+    }
+
+    pub fn get_daily_inflow(env: Env, chain: String) -> i128 {
+        storage::get_daily_inflow(&env, &chain)
+    }
+
+    pub fn get_rate_limits(env: Env) -> Option<RateLimitConfig> {
+        storage::get_rate_limits(&env)
+    }
+
+    pub fn get_inflow_window(env: Env) -> Option<InflowWindow> {
+        storage::get_inflow_window(&env)
+    }
+
+    pub fn is_relayer(env: Env, relayer: Address) -> bool {
+        storage::is_relayer(&env, &relayer)
+    }
+
+    pub fn is_paused(env: Env) -> bool {
+        storage::is_paused(&env)
+    }
+
+    /// The message oracles sign for `bridge_id`: the XDR encoding of
+    /// `(version, bridge contract, bridge_id, source_chain, sender, recipient,
+    /// amount)`. Including this contract's address keeps signatures for one
+    /// deployment from being replayed on another.
+    pub fn attestation_payload(env: Env, bridge_id: String) -> Result<Bytes, Error> {
+        let record = storage::get_bridge(&env, &bridge_id).ok_or(Error::BridgeNotFound)?;
+        Ok(build_attestation_payload(&env, &record))
+    }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Return the window `current_ledger` falls in, opening a fresh one once the
+/// previous window has run for `window_ledgers`.
+fn current_window(
+    window: Option<InflowWindow>,
+    limits: &RateLimitConfig,
+    current_ledger: u32,
+) -> InflowWindow {
+    match window {
+        Some(window) if current_ledger - window.started_at < limits.window_ledgers => window,
+        _ => InflowWindow { started_at: current_ledger, total: 0 },
+    }
+}
+
+/// Look up a registered chain, rejecting unknown and paused ones.
+fn enabled_chain(config: &BridgeConfig, chain: &String) -> Result<ChainConfig, Error> {
+    let chain_config = config
+        .supported_chains
+        .get(chain.clone())
+        .ok_or(Error::UnsupportedChain)?;
+    if !chain_config.enabled {
+        return Err(Error::ChainPaused);
+    }
+    Ok(chain_config)
+}
 
-    #[test]
-    fn it_works() {
-        let result = add(2, 2);
-        assert_eq!(result, 4);
+/// Validate `amount` against the global and per-chain bounds and return the
+/// fee owed on it.
+fn validate_amount(
+    config: &BridgeConfig,
+    chain_config: &ChainConfig,
+    amount: i128,
+) -> Result<i128, Error> {
+    if amount <= 0 {
+        return Err(Error::InvalidAmount);
+    }
+    if amount < config.min_amount || amount < chain_config.min_amount {
+        return Err(Error::AmountBelowMinimum);
     }
+    if amount > config.max_amount || amount > chain_config.max_amount {
+        return Err(Error::AmountAboveMaximum);
+    }
+    let fee_basis_points = chain_config
+        .fee_basis_points
+        .unwrap_or(config.fee_basis_points);
+    Ok(amount * fee_basis_points as i128 / BASIS_POINTS)
+}
+
+fn build_attestation_payload(env: &Env, record: &BridgeRecord) -> Bytes {
+    (
+        ATTESTATION_VERSION,
+        env.current_contract_address(),
+        record.bridge_id.clone(),
+        record.source_chain.clone(),
+        record.sender.clone(),
+        record.recipient.clone(),
+        record.amount,
+    )
+        .to_xdr(env)
 }
+
+/// Check that `proof` holds valid signatures over the record's payload from at
+/// least `threshold` distinct oracle keys. An invalid signature traps the host.
+fn verify_attestations(
+    env: &Env,
+    oracle_set: &OracleSet,
+    record: &BridgeRecord,
+    proof: &Vec<Bytes>,
+) -> Result<(), Error> {
+    let payload = build_attestation_payload(env, record);
+    let mut seen: Vec<BytesN<32>> = Vec::new(env);
+
+    for attestation in proof.iter() {
+        if attestation.len() != ATTESTATION_LEN {
+            return Err(Error::InvalidProof);
+        }
+        let public_key: BytesN<32> = attestation
+            .slice(0..32)
+            .try_into()
+            .map_err(|_| Error::InvalidProof)?;
+        let signature: BytesN<64> = attestation
+            .slice(32..ATTESTATION_LEN)
+            .try_into()
+            .map_err(|_| Error::InvalidProof)?;
+
+        if !oracle_set.signers.contains(&public_key) {
+            return Err(Error::InvalidOracleSignature);
+        }
+        if seen.contains(&public_key) {
+            return Err(Error::InvalidProof);
+        }
+
+        env.crypto().ed25519_verify(&public_key, &payload, &signature);
+        seen.push_back(public_key);
+    }
+
+    if seen.len() < oracle_set.threshold {
+        return Err(Error::InvalidOracleSignature);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test;
@@ -1,10 +1,22 @@
-use soroban_sdk::{Env, String};
-use crate::types::{BridgeConfig, BridgeRecord, BridgeBackRecord};
+use soroban_sdk::{Address, Env, String};
+use crate::types::{
+    BridgeBackRecord, BridgeConfig, BridgeRecord, InflowWindow, OracleSet, RateLimitConfig,
+};
 
 const CONFIG_KEY: &str = "CONFIG";
 const BRIDGE_PREFIX: &str = "BRIDGE";
 const BRIDGE_BACK_PREFIX: &str = "BRIDGE_BACK";
 const TOTAL_FEES_KEY: &str = "TOTAL_FEES";
+const ORACLE_SET_KEY: &str = "ORACLE_SET";
+const CHAIN_INFLOW_PREFIX: &str = "INFLOW";
+const RATE_LIMIT_KEY: &str = "RATE_LIMIT";
+const INFLOW_WINDOW_KEY: &str = "WINDOW";
+const RECIPIENT_WINDOW_PREFIX: &str = "RCPT_WINDOW";
+const GUARDIAN_KEY: &str = "GUARDIAN";
+const PAUSED_KEY: &str = "PAUSED";
+const RELAYER_PREFIX: &str = "RELAYER";
+
+const SECONDS_PER_DAY: u64 = 86_400;
 
 pub fn get_config(env: &Env) -> Option<BridgeConfig> {
     env.storage().instance().get(&CONFIG_KEY)
@@ -15,22 +27,22 @@ pub fn set_config(env: &Env, config: &BridgeConfig) {
 }
 
 pub fn get_bridge(env: &Env, bridge_id: &String) -> Option<BridgeRecord> {
-    let key = (BRIDGE_PREFIX, bridge_id);
+    let key = (BRIDGE_PREFIX, bridge_id.clone());
     env.storage().persistent().get(&key)
 }
 
 pub fn set_bridge(env: &Env, bridge_id: &String, record: &BridgeRecord) {
-    let key = (BRIDGE_PREFIX, bridge_id);
+    let key = (BRIDGE_PREFIX, bridge_id.clone());
     env.storage().persistent().set(&key, record);
 }
 
 pub fn get_bridge_back(env: &Env, bridge_id: &String) -> Option<BridgeBackRecord> {
-    let key = (BRIDGE_BACK_PREFIX, bridge_id);
+    let key = (BRIDGE_BACK_PREFIX, bridge_id.clone());
     env.storage().persistent().get(&key)
 }
 
 pub fn set_bridge_back(env: &Env, bridge_id: &String, record: &BridgeBackRecord) {
-    let key = (BRIDGE_BACK_PREFIX, bridge_id);
+    let key = (BRIDGE_BACK_PREFIX, bridge_id.clone());
     env.storage().persistent().set(&key, record);
 }
 
@@ -41,4 +53,86 @@ pub fn get_total_fees(env: &Env) -> i128 {
 pub fn add_fees(env: &Env, amount: i128) {
     let current = get_total_fees(env);
     env.storage().instance().set(&TOTAL_FEES_KEY, &(current + amount));
-}
\ No newline at end of file
+}
+
+pub fn reset_fees(env: &Env) {
+    env.storage().instance().set(&TOTAL_FEES_KEY, &0i128);
+}
+
+pub fn get_oracle_set(env: &Env) -> Option<OracleSet> {
+    env.storage().instance().get(&ORACLE_SET_KEY)
+}
+
+pub fn set_oracle_set(env: &Env, oracle_set: &OracleSet) {
+    env.storage().instance().set(&ORACLE_SET_KEY, oracle_set);
+}
+
+/// Amount minted from `chain` during the current UTC day.
+pub fn get_daily_inflow(env: &Env, chain: &String) -> i128 {
+    let day = env.ledger().timestamp() / SECONDS_PER_DAY;
+    let key = (CHAIN_INFLOW_PREFIX, chain.clone(), day);
+    env.storage().persistent().get(&key).unwrap_or(0)
+}
+
+pub fn add_daily_inflow(env: &Env, chain: &String, amount: i128) {
+    let day = env.ledger().timestamp() / SECONDS_PER_DAY;
+    let key = (CHAIN_INFLOW_PREFIX, chain.clone(), day);
+    let current: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+    env.storage().persistent().set(&key, &(current + amount));
+}
+
+pub fn get_rate_limits(env: &Env) -> Option<RateLimitConfig> {
+    env.storage().instance().get(&RATE_LIMIT_KEY)
+}
+
+pub fn set_rate_limits(env: &Env, limits: &RateLimitConfig) {
+    env.storage().instance().set(&RATE_LIMIT_KEY, limits);
+}
+
+pub fn get_inflow_window(env: &Env) -> Option<InflowWindow> {
+    env.storage().instance().get(&INFLOW_WINDOW_KEY)
+}
+
+pub fn set_inflow_window(env: &Env, window: &InflowWindow) {
+    env.storage().instance().set(&INFLOW_WINDOW_KEY, window);
+}
+
+pub fn get_recipient_window(env: &Env, recipient: &Address) -> Option<InflowWindow> {
+    let key = (RECIPIENT_WINDOW_PREFIX, recipient.clone());
+    env.storage().persistent().get(&key)
+}
+
+pub fn set_recipient_window(env: &Env, recipient: &Address, window: &InflowWindow) {
+    let key = (RECIPIENT_WINDOW_PREFIX, recipient.clone());
+    env.storage().persistent().set(&key, window);
+}
+
+pub fn get_guardian(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&GUARDIAN_KEY)
+}
+
+pub fn set_guardian(env: &Env, guardian: &Address) {
+    env.storage().instance().set(&GUARDIAN_KEY, guardian);
+}
+
+pub fn is_relayer(env: &Env, relayer: &Address) -> bool {
+    let key = (RELAYER_PREFIX, relayer.clone());
+    env.storage().persistent().has(&key)
+}
+
+pub fn set_relayer(env: &Env, relayer: &Address, allowed: bool) {
+    let key = (RELAYER_PREFIX, relayer.clone());
+    if allowed {
+        env.storage().persistent().set(&key, &true);
+    } else {
+        env.storage().persistent().remove(&key);
+    }
+}
+
+pub fn is_paused(env: &Env) -> bool {
+    env.storage().instance().get(&PAUSED_KEY).unwrap_or(false)
+}
+
+pub fn set_paused(env: &Env, paused: bool) {
+    env.storage().instance().set(&PAUSED_KEY, &paused);
+}
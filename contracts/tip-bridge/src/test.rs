@@ -0,0 +1,795 @@
+#![cfg(test)]
+extern crate std;
+
+use super::*;
+use ed25519_dalek::{Signer, SigningKey};
+use soroban_sdk::{
+    symbol_short,
+    testutils::{Address as _, Events, Ledger},
+    token, vec, Address, Bytes, BytesN, Env, String, Symbol, TryFromVal,
+};
+
+const TTL_LEDGERS: u32 = 100;
+
+struct Setup {
+    env: Env,
+    client: TipBridgeContractClient<'static>,
+    oracle: Address,
+    fee_collector: Address,
+    relayer: Address,
+    token: token::Client<'static>,
+}
+
+/// A bridge with a single oracle key, `oracle_key(0)`, attesting completions.
+fn setup() -> Setup {
+    let s = setup_without_oracle_set();
+    s.client.set_oracle_signers(&vec![&s.env, public_key(&s.env, &oracle_key(0))], &1);
+    s
+}
+
+#[allow(deprecated)]
+fn setup_without_oracle_set() -> Setup {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, TipBridgeContract);
+    let client = TipBridgeContractClient::new(&env, &contract_id);
+
+    // The bridge contract is the admin of the wrapped token so it can mint.
+    let token_id = env.register_stellar_asset_contract(contract_id.clone());
+    let token = token::Client::new(&env, &token_id);
+
+    let admin = Address::generate(&env);
+    let oracle = Address::generate(&env);
+    let fee_collector = Address::generate(&env);
+    let relayer = Address::generate(&env);
+
+    client.initialize(
+        &admin,
+        &oracle,
+        &token_id,
+        &fee_collector,
+        &100, // 1%
+        &100,
+        &1_000_000,
+        &TTL_LEDGERS,
+    );
+    client.set_chain(&String::from_str(&env, "ethereum"), &chain_config(true));
+    client.set_relayer(&relayer, &true);
+
+    Setup {
+        env,
+        client,
+        oracle,
+        fee_collector,
+        relayer,
+        token,
+    }
+}
+
+fn chain_config(enabled: bool) -> ChainConfig {
+    ChainConfig {
+        enabled,
+        min_amount: 100,
+        max_amount: 1_000_000,
+        fee_basis_points: None,
+        daily_inflow_cap: 0,
+    }
+}
+
+fn initiate(s: &Setup, id: &str, recipient: &Address, amount: i128) -> String {
+    initiate_from(s, "ethereum", id, recipient, amount)
+}
+
+fn initiate_from(s: &Setup, chain: &str, id: &str, recipient: &Address, amount: i128) -> String {
+    let bridge_id = String::from_str(&s.env, id);
+    s.client.initiate_bridge(
+        &s.relayer,
+        &bridge_id,
+        &String::from_str(&s.env, chain),
+        &Bytes::from_array(&s.env, &[0xab; 20]),
+        recipient,
+        &amount,
+    );
+    bridge_id
+}
+
+fn advance_ledgers(env: &Env, ledgers: u32) {
+    env.ledger().with_mut(|li| {
+        li.sequence_number += ledgers;
+    });
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #2)")]
+fn test_initialize_twice() {
+    let s = setup();
+    let config = s.client.get_config();
+    s.client.initialize(
+        &config.admin,
+        &config.oracle,
+        &config.wrapped_token,
+        &config.fee_collector,
+        &config.fee_basis_points,
+        &config.min_amount,
+        &config.max_amount,
+        &config.bridge_ttl_ledgers,
+    );
+}
+
+#[test]
+fn test_complete_bridge_mints_net_amount() {
+    let s = setup();
+    let recipient = Address::generate(&s.env);
+
+    let bridge_id = initiate(&s, "tx-1", &recipient, 10_000);
+    let record = s.client.get_bridge(&bridge_id);
+    assert_eq!(record.status, BridgeStatus::Pending);
+    assert_eq!(record.fee, 100);
+    assert_eq!(record.net_amount, 9_900);
+
+    let net = s.client.complete_bridge(&bridge_id, &proof(&s, &bridge_id));
+    assert_eq!(net, 9_900);
+
+    // The oracle's signature is the only attestation needed.
+    assert!(s.env.auths().is_empty());
+
+    assert_eq!(s.token.balance(&recipient), 9_900);
+    assert_eq!(s.token.balance(&s.client.address), 100);
+    assert_eq!(s.client.get_total_fees(), 100);
+    assert_eq!(s.client.get_bridge(&bridge_id).status, BridgeStatus::Completed);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #5)")]
+fn test_complete_bridge_twice() {
+    let s = setup();
+    let recipient = Address::generate(&s.env);
+    let bridge_id = initiate(&s, "tx-1", &recipient, 10_000);
+
+    s.client.complete_bridge(&bridge_id, &proof(&s, &bridge_id));
+    s.client.complete_bridge(&bridge_id, &proof(&s, &bridge_id));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #16)")]
+fn test_duplicate_bridge_id_rejected() {
+    let s = setup();
+    let recipient = Address::generate(&s.env);
+    initiate(&s, "tx-1", &recipient, 10_000);
+    initiate(&s, "tx-1", &recipient, 10_000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #13)")]
+fn test_amount_below_minimum() {
+    let s = setup();
+    let recipient = Address::generate(&s.env);
+    initiate(&s, "tx-1", &recipient, 99);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #14)")]
+fn test_amount_above_maximum() {
+    let s = setup();
+    let recipient = Address::generate(&s.env);
+    initiate(&s, "tx-1", &recipient, 1_000_001);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #11)")]
+fn test_complete_expired_bridge() {
+    let s = setup();
+    let recipient = Address::generate(&s.env);
+    let bridge_id = initiate(&s, "tx-1", &recipient, 10_000);
+
+    advance_ledgers(&s.env, TTL_LEDGERS + 1);
+    s.client.complete_bridge(&bridge_id, &proof(&s, &bridge_id));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #17)")]
+fn test_cancel_before_expiry() {
+    let s = setup();
+    let recipient = Address::generate(&s.env);
+    let bridge_id = initiate(&s, "tx-1", &recipient, 10_000);
+
+    advance_ledgers(&s.env, TTL_LEDGERS);
+    s.client.cancel_expired_bridge(&bridge_id);
+}
+
+#[test]
+fn test_cancel_expired_bridge() {
+    let s = setup();
+    let recipient = Address::generate(&s.env);
+    let bridge_id = initiate(&s, "tx-1", &recipient, 10_000);
+
+    advance_ledgers(&s.env, TTL_LEDGERS + 1);
+    s.client.cancel_expired_bridge(&bridge_id);
+
+    assert_eq!(s.client.get_bridge(&bridge_id).status, BridgeStatus::Cancelled);
+    assert_eq!(s.token.balance(&recipient), 0);
+    assert_eq!(
+        s.client.try_complete_bridge(&bridge_id, &proof(&s, &bridge_id)),
+        Err(Ok(Error::AlreadyCancelled))
+    );
+}
+
+#[test]
+fn test_unregistered_relayer_cannot_squat_bridge_id() {
+    let s = setup();
+    let attacker = Address::generate(&s.env);
+    let bridge_id = String::from_str(&s.env, "tx-1");
+    let chain = String::from_str(&s.env, "ethereum");
+    let sender = Bytes::from_array(&s.env, &[0xab; 20]);
+
+    assert_eq!(
+        s.client.try_initiate_bridge(&attacker, &bridge_id, &chain, &sender, &attacker, &10_000),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert!(matches!(s.client.try_get_bridge(&bridge_id), Err(Ok(Error::BridgeNotFound))));
+
+    // Revoked relayers lose access too.
+    s.client.set_relayer(&s.relayer, &false);
+    assert!(!s.client.is_relayer(&s.relayer));
+    assert_eq!(
+        s.client.try_initiate_bridge(&s.relayer, &bridge_id, &chain, &sender, &attacker, &10_000),
+        Err(Ok(Error::Unauthorized))
+    );
+}
+
+#[test]
+fn test_cancelled_bridge_id_can_be_initiated_again() {
+    let s = setup();
+    let wrong_recipient = Address::generate(&s.env);
+    let recipient = Address::generate(&s.env);
+    let bridge_id = initiate(&s, "tx-1", &wrong_recipient, 10_000);
+
+    advance_ledgers(&s.env, TTL_LEDGERS + 1);
+    s.client.cancel_expired_bridge(&bridge_id);
+
+    initiate(&s, "tx-1", &recipient, 10_000);
+    let record = s.client.get_bridge(&bridge_id);
+    assert_eq!(record.status, BridgeStatus::Pending);
+    assert_eq!(record.recipient, recipient);
+
+    s.client.complete_bridge(&bridge_id, &proof(&s, &bridge_id));
+    assert_eq!(s.token.balance(&recipient), 9_900);
+    assert_eq!(s.token.balance(&wrong_recipient), 0);
+}
+
+#[test]
+fn test_bridge_back_burns_net_and_keeps_fee() {
+    let s = setup();
+    let user = Address::generate(&s.env);
+    let bridge_id = initiate(&s, "tx-1", &user, 20_000);
+    s.client.complete_bridge(&bridge_id, &proof(&s, &bridge_id));
+    assert_eq!(s.token.balance(&user), 19_800);
+
+    let back_id = String::from_str(&s.env, "back-1");
+    s.client.initiate_bridge_back(
+        &user,
+        &back_id,
+        &String::from_str(&s.env, "ethereum"),
+        &Bytes::from_array(&s.env, &[0xcd; 20]),
+        &10_000,
+    );
+
+    let record = s.client.get_bridge_back(&back_id);
+    assert_eq!(record.fee, 100);
+    assert_eq!(record.net_amount, 9_900);
+    assert_eq!(s.token.balance(&user), 9_800);
+    // 200 in inbound fees + 100 in outbound fees; the net amount was burned.
+    assert_eq!(s.token.balance(&s.client.address), 300);
+    assert_eq!(s.client.get_total_fees(), 300);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #8)")]
+fn test_bridge_back_insufficient_balance() {
+    let s = setup();
+    let user = Address::generate(&s.env);
+    s.client.initiate_bridge_back(
+        &user,
+        &String::from_str(&s.env, "back-1"),
+        &String::from_str(&s.env, "ethereum"),
+        &Bytes::from_array(&s.env, &[0xcd; 20]),
+        &10_000,
+    );
+}
+
+#[test]
+fn test_withdraw_fees() {
+    let s = setup();
+    let recipient = Address::generate(&s.env);
+    let bridge_id = initiate(&s, "tx-1", &recipient, 50_000);
+    s.client.complete_bridge(&bridge_id, &proof(&s, &bridge_id));
+
+    let withdrawn = s.client.withdraw_fees();
+    assert_eq!(withdrawn, 500);
+    assert_eq!(s.token.balance(&s.fee_collector), 500);
+    assert_eq!(s.client.get_total_fees(), 0);
+    assert_eq!(s.client.try_withdraw_fees(), Err(Ok(Error::InsufficientBalance)));
+}
+
+fn oracle_key(seed: u8) -> SigningKey {
+    SigningKey::from_bytes(&[seed; 32])
+}
+
+fn public_key(env: &Env, key: &SigningKey) -> BytesN<32> {
+    BytesN::from_array(env, &key.verifying_key().to_bytes())
+}
+
+fn attest(s: &Setup, key: &SigningKey, bridge_id: &String) -> Bytes {
+    let payload: std::vec::Vec<u8> = s.client.attestation_payload(bridge_id).iter().collect();
+    let mut attestation = Bytes::from_array(&s.env, &key.verifying_key().to_bytes());
+    attestation.extend_from_array(&key.sign(&payload).to_bytes());
+    attestation
+}
+
+/// Attestation from the single oracle key `setup` configures.
+fn proof(s: &Setup, bridge_id: &String) -> Vec<Bytes> {
+    vec![&s.env, attest(s, &oracle_key(0), bridge_id)]
+}
+
+fn set_two_of_three(s: &Setup) -> [SigningKey; 3] {
+    let keys = [oracle_key(1), oracle_key(2), oracle_key(3)];
+    let signers = vec![
+        &s.env,
+        public_key(&s.env, &keys[0]),
+        public_key(&s.env, &keys[1]),
+        public_key(&s.env, &keys[2]),
+    ];
+    s.client.set_oracle_signers(&signers, &2);
+    keys
+}
+
+#[test]
+fn test_complete_bridge_with_oracle_signatures() {
+    let s = setup();
+    let keys = set_two_of_three(&s);
+    let recipient = Address::generate(&s.env);
+    let bridge_id = initiate(&s, "tx-1", &recipient, 10_000);
+
+    let proof = vec![
+        &s.env,
+        attest(&s, &keys[0], &bridge_id),
+        attest(&s, &keys[2], &bridge_id),
+    ];
+    s.client.complete_bridge(&bridge_id, &proof);
+
+    // Signatures replace the oracle's auth entirely.
+    assert!(s.env.auths().is_empty());
+    assert_eq!(s.token.balance(&recipient), 9_900);
+
+    let record = s.client.get_bridge(&bridge_id);
+    assert_eq!(record.status, BridgeStatus::Completed);
+    assert_eq!(record.proof, proof);
+}
+
+#[test]
+fn test_complete_bridge_below_threshold() {
+    let s = setup();
+    let keys = set_two_of_three(&s);
+    let recipient = Address::generate(&s.env);
+    let bridge_id = initiate(&s, "tx-1", &recipient, 10_000);
+
+    let proof = vec![&s.env, attest(&s, &keys[1], &bridge_id)];
+    assert_eq!(
+        s.client.try_complete_bridge(&bridge_id, &proof),
+        Err(Ok(Error::InvalidOracleSignature))
+    );
+    assert_eq!(
+        s.client.try_complete_bridge(&bridge_id, &vec![&s.env]),
+        Err(Ok(Error::InvalidOracleSignature))
+    );
+}
+
+#[test]
+fn test_complete_bridge_rejects_unknown_and_duplicate_signers() {
+    let s = setup();
+    let keys = set_two_of_three(&s);
+    let recipient = Address::generate(&s.env);
+    let bridge_id = initiate(&s, "tx-1", &recipient, 10_000);
+
+    let outsider = oracle_key(9);
+    let proof = vec![
+        &s.env,
+        attest(&s, &keys[0], &bridge_id),
+        attest(&s, &outsider, &bridge_id),
+    ];
+    assert_eq!(
+        s.client.try_complete_bridge(&bridge_id, &proof),
+        Err(Ok(Error::InvalidOracleSignature))
+    );
+
+    let signature = attest(&s, &keys[0], &bridge_id);
+    let proof = vec![&s.env, signature.clone(), signature];
+    assert_eq!(
+        s.client.try_complete_bridge(&bridge_id, &proof),
+        Err(Ok(Error::InvalidProof))
+    );
+
+    let proof = vec![&s.env, Bytes::from_array(&s.env, &[0u8; 64])];
+    assert_eq!(
+        s.client.try_complete_bridge(&bridge_id, &proof),
+        Err(Ok(Error::InvalidProof))
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Crypto, InvalidInput)")]
+fn test_complete_bridge_rejects_signature_for_other_transfer() {
+    let s = setup();
+    let keys = set_two_of_three(&s);
+    let recipient = Address::generate(&s.env);
+    let bridge_id = initiate(&s, "tx-1", &recipient, 10_000);
+    let other_id = initiate(&s, "tx-2", &recipient, 20_000);
+
+    // Valid oracle signatures, but over a different transfer's payload.
+    let proof = vec![
+        &s.env,
+        attest(&s, &keys[0], &other_id),
+        attest(&s, &keys[1], &other_id),
+    ];
+    s.client.complete_bridge(&bridge_id, &proof);
+}
+
+#[test]
+#[should_panic(expected = "Error(Crypto, InvalidInput)")]
+fn test_complete_bridge_rejects_signature_for_other_deployment() {
+    let s = setup();
+    let keys = set_two_of_three(&s);
+    let recipient = Address::generate(&s.env);
+    let bridge_id = initiate(&s, "tx-1", &recipient, 10_000);
+
+    // A second bridge trusting the same oracle keys, with the same transfer pending.
+    let other = TipBridgeContractClient::new(&s.env, &s.env.register_contract(None, TipBridgeContract));
+    let other_token = s.env.register_stellar_asset_contract_v2(other.address.clone()).address();
+    other.initialize(
+        &Address::generate(&s.env),
+        &s.oracle,
+        &other_token,
+        &s.fee_collector,
+        &100,
+        &100,
+        &1_000_000,
+        &TTL_LEDGERS,
+    );
+    other.set_chain(&String::from_str(&s.env, "ethereum"), &chain_config(true));
+    other.set_relayer(&s.relayer, &true);
+    other.set_oracle_signers(&s.client.get_oracle_set().unwrap().signers, &2);
+    other.initiate_bridge(
+        &s.relayer,
+        &bridge_id,
+        &String::from_str(&s.env, "ethereum"),
+        &Bytes::from_array(&s.env, &[0xab; 20]),
+        &recipient,
+        &10_000,
+    );
+
+    let payload: std::vec::Vec<u8> = other.attestation_payload(&bridge_id).iter().collect();
+    let mut proof = Vec::new(&s.env);
+    for key in &keys[..2] {
+        let mut attestation = Bytes::from_array(&s.env, &key.verifying_key().to_bytes());
+        attestation.extend_from_array(&key.sign(&payload).to_bytes());
+        proof.push_back(attestation);
+    }
+    other.complete_bridge(&bridge_id, &proof);
+    s.client.complete_bridge(&bridge_id, &proof);
+}
+
+#[test]
+fn test_complete_bridge_requires_oracle_set() {
+    let s = setup_without_oracle_set();
+    let recipient = Address::generate(&s.env);
+    let bridge_id = initiate(&s, "tx-1", &recipient, 10_000);
+
+    // Without oracle keys nothing binds a completion to the transfer.
+    assert_eq!(
+        s.client.try_complete_bridge(&bridge_id, &vec![&s.env]),
+        Err(Ok(Error::InvalidOracleSet))
+    );
+    assert_eq!(s.token.balance(&recipient), 0);
+    assert_eq!(s.client.get_bridge(&bridge_id).status, BridgeStatus::Pending);
+}
+
+#[test]
+fn test_set_oracle_signers_validation() {
+    let s = setup();
+    let key = public_key(&s.env, &oracle_key(1));
+
+    assert_eq!(
+        s.client.try_set_oracle_signers(&vec![&s.env, key.clone()], &0),
+        Err(Ok(Error::InvalidOracleSet))
+    );
+    assert_eq!(
+        s.client.try_set_oracle_signers(&vec![&s.env, key.clone()], &2),
+        Err(Ok(Error::InvalidOracleSet))
+    );
+    assert_eq!(
+        s.client.try_set_oracle_signers(&vec![&s.env, key.clone(), key.clone()], &1),
+        Err(Ok(Error::InvalidOracleSet))
+    );
+
+    s.client.set_oracle_signers(&vec![&s.env, key], &1);
+    assert_eq!(s.client.get_oracle_set().unwrap().threshold, 1);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #10)")]
+fn test_initiate_from_unknown_chain() {
+    let s = setup();
+    let recipient = Address::generate(&s.env);
+    initiate_from(&s, "solana", "tx-1", &recipient, 10_000);
+}
+
+#[test]
+fn test_paused_chain_rejects_bridges() {
+    let s = setup();
+    let user = Address::generate(&s.env);
+    let ethereum = String::from_str(&s.env, "ethereum");
+    let bridge_id = initiate(&s, "tx-1", &user, 10_000);
+
+    s.client.set_chain_enabled(&ethereum, &false);
+    assert!(!s.client.get_chain(&ethereum).enabled);
+
+    assert_eq!(
+        s.client.try_initiate_bridge(
+            &s.relayer,
+            &String::from_str(&s.env, "tx-2"),
+            &ethereum,
+            &Bytes::from_array(&s.env, &[0xab; 20]),
+            &user,
+            &10_000,
+        ),
+        Err(Ok(Error::ChainPaused))
+    );
+    assert_eq!(
+        s.client.try_complete_bridge(&bridge_id, &proof(&s, &bridge_id)),
+        Err(Ok(Error::ChainPaused))
+    );
+    assert_eq!(
+        s.client.try_initiate_bridge_back(
+            &user,
+            &String::from_str(&s.env, "back-1"),
+            &ethereum,
+            &Bytes::from_array(&s.env, &[0xcd; 20]),
+            &100,
+        ),
+        Err(Ok(Error::ChainPaused))
+    );
+
+    s.client.set_chain_enabled(&ethereum, &true);
+    s.client.complete_bridge(&bridge_id, &proof(&s, &bridge_id));
+    assert_eq!(s.token.balance(&user), 9_900);
+}
+
+#[test]
+fn test_removed_chain_is_unsupported() {
+    let s = setup();
+    let recipient = Address::generate(&s.env);
+    let ethereum = String::from_str(&s.env, "ethereum");
+    let bridge_id = initiate(&s, "tx-1", &recipient, 10_000);
+
+    s.client.remove_chain(&ethereum);
+    assert_eq!(s.client.try_get_chain(&ethereum), Err(Ok(Error::UnsupportedChain)));
+    assert_eq!(
+        s.client.try_complete_bridge(&bridge_id, &proof(&s, &bridge_id)),
+        Err(Ok(Error::UnsupportedChain))
+    );
+}
+
+#[test]
+fn test_per_chain_limits_and_fee_override() {
+    let s = setup();
+    let recipient = Address::generate(&s.env);
+    s.client.set_chain(
+        &String::from_str(&s.env, "polygon"),
+        &ChainConfig {
+            enabled: true,
+            min_amount: 1_000,
+            max_amount: 5_000,
+            fee_basis_points: Some(200),
+            daily_inflow_cap: 0,
+        },
+    );
+
+    // Inside the global bounds but outside the chain's own bounds.
+    assert_eq!(
+        s.client.try_initiate_bridge(
+            &s.relayer,
+            &String::from_str(&s.env, "tx-1"),
+            &String::from_str(&s.env, "polygon"),
+            &Bytes::from_array(&s.env, &[0xab; 20]),
+            &recipient,
+            &999,
+        ),
+        Err(Ok(Error::AmountBelowMinimum))
+    );
+    assert_eq!(
+        s.client.try_initiate_bridge(
+            &s.relayer,
+            &String::from_str(&s.env, "tx-1"),
+            &String::from_str(&s.env, "polygon"),
+            &Bytes::from_array(&s.env, &[0xab; 20]),
+            &recipient,
+            &5_001,
+        ),
+        Err(Ok(Error::AmountAboveMaximum))
+    );
+
+    let bridge_id = initiate_from(&s, "polygon", "tx-1", &recipient, 5_000);
+    assert_eq!(s.client.get_bridge(&bridge_id).fee, 100);
+}
+
+#[test]
+fn test_daily_inflow_cap() {
+    let s = setup();
+    let recipient = Address::generate(&s.env);
+    let ethereum = String::from_str(&s.env, "ethereum");
+    s.client.set_chain(
+        &ethereum,
+        &ChainConfig {
+            daily_inflow_cap: 15_000,
+            ..chain_config(true)
+        },
+    );
+
+    let first = initiate(&s, "tx-1", &recipient, 10_000);
+    let second = initiate(&s, "tx-2", &recipient, 10_000);
+    s.client.complete_bridge(&first, &proof(&s, &first));
+    assert_eq!(s.client.get_daily_inflow(&ethereum), 10_000);
+    assert_eq!(
+        s.client.try_complete_bridge(&second, &proof(&s, &second)),
+        Err(Ok(Error::DailyCapExceeded))
+    );
+
+    // The cap resets on the next day.
+    s.env.ledger().with_mut(|li| {
+        li.timestamp += 86_400;
+    });
+    s.client.complete_bridge(&second, &proof(&s, &second));
+    assert_eq!(s.client.get_daily_inflow(&ethereum), 10_000);
+}
+
+#[test]
+fn test_set_chain_validation() {
+    let s = setup();
+    let chain = String::from_str(&s.env, "polygon");
+    assert_eq!(
+        s.client.try_set_chain(
+            &chain,
+            &ChainConfig {
+                fee_basis_points: Some(MAX_FEE_BASIS_POINTS + 1),
+                ..chain_config(true)
+            },
+        ),
+        Err(Ok(Error::FeeTooHigh))
+    );
+    assert_eq!(
+        s.client.try_set_chain(
+            &chain,
+            &ChainConfig {
+                min_amount: 10,
+                max_amount: 5,
+                ..chain_config(true)
+            },
+        ),
+        Err(Ok(Error::InvalidAmount))
+    );
+    assert_eq!(
+        s.client.try_set_chain_enabled(&chain, &true),
+        Err(Ok(Error::UnsupportedChain))
+    );
+}
+
+fn rate_limits(window_cap: i128, recipient_cap: i128) -> RateLimitConfig {
+    RateLimitConfig {
+        window_ledgers: 50,
+        window_inflow_cap: window_cap,
+        recipient_inflow_cap: recipient_cap,
+    }
+}
+
+#[test]
+fn test_guardian_can_pause_but_not_unpause() {
+    let s = setup();
+    let guardian = Address::generate(&s.env);
+    let user = Address::generate(&s.env);
+    s.client.set_guardian(&guardian);
+
+    assert_eq!(s.client.try_pause_bridge(&user), Err(Ok(Error::Unauthorized)));
+
+    s.client.pause_bridge(&guardian);
+    assert!(s.client.is_paused());
+    assert_eq!(
+        s.client.try_initiate_bridge(
+            &s.relayer,
+            &String::from_str(&s.env, "tx-1"),
+            &String::from_str(&s.env, "ethereum"),
+            &Bytes::from_array(&s.env, &[0xab; 20]),
+            &user,
+            &10_000,
+        ),
+        Err(Ok(Error::BridgePaused))
+    );
+
+    s.client.unpause_bridge();
+    let auths = s.env.auths();
+    assert_eq!(auths[0].0, s.client.get_config().admin);
+    assert!(!s.client.is_paused());
+    initiate(&s, "tx-1", &user, 10_000);
+}
+
+#[test]
+fn test_window_cap_trips_circuit_breaker() {
+    let s = setup();
+    let recipient = Address::generate(&s.env);
+    s.client.set_rate_limits(&rate_limits(25_000, 0));
+
+    let first = initiate(&s, "tx-1", &recipient, 20_000);
+    let second = initiate(&s, "tx-2", &recipient, 10_000);
+    s.client.complete_bridge(&first, &proof(&s, &first));
+
+    let minted = s.client.complete_bridge(&second, &proof(&s, &second));
+    assert_eq!(minted, 0);
+    assert!(s.client.is_paused());
+    assert_eq!(s.client.get_bridge(&second).status, BridgeStatus::Pending);
+    assert_eq!(s.token.balance(&recipient), 19_800);
+    assert_eq!(s.client.get_inflow_window().unwrap().total, 20_000);
+
+    let breaker_events = s
+        .env
+        .events()
+        .all()
+        .iter()
+        .filter(|(_, topics, _)| {
+            topics.get(1).map(|t| Symbol::try_from_val(&s.env, &t).ok())
+                == Some(Some(symbol_short!("BREAKER")))
+        })
+        .count();
+    assert_eq!(breaker_events, 1);
+
+    assert_eq!(
+        s.client.try_complete_bridge(&second, &proof(&s, &second)),
+        Err(Ok(Error::BridgePaused))
+    );
+}
+
+#[test]
+fn test_window_cap_resets_after_window() {
+    let s = setup();
+    let recipient = Address::generate(&s.env);
+    s.client.set_rate_limits(&rate_limits(25_000, 0));
+
+    let first = initiate(&s, "tx-1", &recipient, 20_000);
+    s.client.complete_bridge(&first, &proof(&s, &first));
+
+    advance_ledgers(&s.env, 50);
+    let second = initiate(&s, "tx-2", &recipient, 20_000);
+    assert_eq!(s.client.complete_bridge(&second, &proof(&s, &second)), 19_800);
+    assert!(!s.client.is_paused());
+    assert_eq!(s.client.get_inflow_window().unwrap().total, 20_000);
+}
+
+#[test]
+fn test_recipient_cap() {
+    let s = setup();
+    let alice = Address::generate(&s.env);
+    let bob = Address::generate(&s.env);
+    s.client.set_rate_limits(&rate_limits(0, 15_000));
+
+    let first = initiate(&s, "tx-1", &alice, 10_000);
+    let second = initiate(&s, "tx-2", &alice, 10_000);
+    let third = initiate(&s, "tx-3", &bob, 10_000);
+    s.client.complete_bridge(&first, &proof(&s, &first));
+
+    assert_eq!(
+        s.client.try_complete_bridge(&second, &proof(&s, &second)),
+        Err(Ok(Error::RecipientCapExceeded))
+    );
+    assert!(!s.client.is_paused());
+    s.client.complete_bridge(&third, &proof(&s, &third));
+    assert_eq!(s.token.balance(&bob), 9_900);
+}
@@ -1,4 +1,4 @@
-use soroban_sdk::{contracttype, Address, Bytes, String, Vec};
+use soroban_sdk::{contracttype, Address, Bytes, BytesN, Map, String, Vec};
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -19,7 +19,7 @@ pub struct BridgeRecord {
     pub fee: i128,
     pub net_amount: i128,
     pub initiated_at: u64,
-    pub expires_at: u64,
+    pub expires_at: u64,          // ledger sequence
     pub status: BridgeStatus,
     pub proof: Vec<Bytes>,
 }
@@ -49,4 +49,44 @@ pub struct BridgeConfig {
     pub min_amount: i128,
     pub max_amount: i128,
     pub bridge_ttl_ledgers: u32,
-}
\ No newline at end of file
+    pub supported_chains: Map<String, ChainConfig>,
+}
+
+/// Per-chain settings for a registered source/destination chain. Amount limits
+/// apply on top of the global `BridgeConfig` limits.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ChainConfig {
+    pub enabled: bool,
+    pub min_amount: i128,
+    pub max_amount: i128,
+    pub fee_basis_points: Option<u32>, // overrides BridgeConfig.fee_basis_points
+    pub daily_inflow_cap: i128,        // 0 = uncapped
+}
+
+/// Ed25519 keys whose signatures attest inbound transfers. A completion needs
+/// signatures from at least `threshold` distinct keys.
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct OracleSet {
+    pub signers: Vec<BytesN<32>>,
+    pub threshold: u32,
+}
+
+/// Inflow limits applied over a window of `window_ledgers` ledgers. Caps of 0
+/// are uncapped.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RateLimitConfig {
+    pub window_ledgers: u32,
+    pub window_inflow_cap: i128,
+    pub recipient_inflow_cap: i128,
+}
+
+/// Running inflow total for the window that opened at ledger `started_at`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InflowWindow {
+    pub started_at: u32,
+    pub total: i128,
+}
@@ -6,14 +6,52 @@ mod types;
 #[cfg(test)]
 mod test;
 
-use soroban_sdk::{contract, contractimpl, symbol_short, token, Address, Env, String, Vec};
-use types::{Asset, Error, EscrowStatus, RoyaltySplit, TipEscrow, TipRecord};
+use soroban_sdk::{
+    contract, contractimpl, symbol_short, token, xdr::ToXdr, Address, BytesN, Env, String, Vec,
+};
+use types::{
+    Asset, Error, EscrowStatus, RoyaltySplit, TipEscrow, TipMetadata, TipPage, TipRecord,
+};
+
+/// Largest number of tips returned by a single history query
+const MAX_PAGE_SIZE: u32 = 50;
+const MAX_MESSAGE_LEN: u32 = 280;
+const MAX_TRACK_ID_LEN: u32 = 64;
+const MAX_MEMO_LEN: u32 = 64;
 
 #[contract]
 pub struct TipEscrowContract;
 
 #[contractimpl]
 impl TipEscrowContract {
+    /// Set the admin, the arbiter who settles escrow disputes, and the
+    /// Stellar Asset Contract used for native XLM escrows
+    pub fn initialize(
+        env: Env,
+        admin: Address,
+        arbiter: Address,
+        native_token: Address,
+    ) -> Result<(), Error> {
+        if storage::get_admin(&env).is_some() {
+            return Err(Error::AlreadyInitialized);
+        }
+        admin.require_auth();
+
+        storage::set_admin(&env, &admin);
+        storage::set_arbiter(&env, &arbiter);
+        storage::set_native_token(&env, &native_token);
+        Ok(())
+    }
+
+    /// Replace the dispute arbiter (admin only)
+    pub fn set_arbiter(env: Env, arbiter: Address) -> Result<(), Error> {
+        let admin = storage::get_admin(&env).ok_or(Error::NotInitialized)?;
+        admin.require_auth();
+
+        storage::set_arbiter(&env, &arbiter);
+        Ok(())
+    }
+
     pub fn create_escrow(
         env: Env,
         tipper: Address,
@@ -21,20 +59,18 @@ impl TipEscrowContract {
         amount: i128,
         asset: Asset,
         release_time: u64,
+        metadata: Option<TipMetadata>,
     ) -> Result<String, Error> {
         tipper.require_auth();
 
         if amount <= 0 {
             return Err(Error::InvalidAmount);
         }
+        let metadata = validate_metadata(metadata)?;
 
         // Lock funds inside contract
-        match &asset {
-            Asset::Token(token_address) => {
-                let token_client = token::Client::new(&env, token_address);
-                token_client.transfer(&tipper, &env.current_contract_address(), &amount);
-            }
-        }
+        let token_client = token::Client::new(&env, &resolve_asset(&env, &asset)?);
+        token_client.transfer(&tipper, &env.current_contract_address(), &amount);
 
         let mut counter: u32 = env
             .storage()
@@ -61,6 +97,7 @@ impl TipEscrowContract {
         }
 
         let escrow_id = String::from_bytes(&env, &buf[i..]);
+        let splits = storage::get_splits(&env, &artist).unwrap_or(Vec::new(&env));
 
         let escrow = TipEscrow {
             escrow_id: escrow_id.clone(),
@@ -71,6 +108,8 @@ impl TipEscrowContract {
             status: EscrowStatus::Pending,
             release_time,
             created_at: env.ledger().timestamp(),
+            splits,
+            metadata,
         };
 
         storage::save_escrow(&env, escrow_id.clone(), &escrow);
@@ -88,22 +127,150 @@ impl TipEscrowContract {
         storage::get_escrow(&env, escrow_id).ok_or(Error::EscrowNotFound)
     }
 
-    /// Send a tip to an artist with optional royalty distribution
+    /// Pay a pending escrow out to the artist and their collaborators once its
+    /// release time has passed
+    pub fn release_escrow(env: Env, escrow_id: String) -> Result<(), Error> {
+        let mut escrow = Self::get_escrow(env.clone(), escrow_id.clone())?;
+        escrow.artist.require_auth();
+
+        if escrow.status != EscrowStatus::Pending {
+            return Err(Error::InvalidStatus);
+        }
+        if env.ledger().timestamp() < escrow.release_time {
+            return Err(Error::ReleaseTimeNotReached);
+        }
+
+        pay_artist(&env, &escrow, escrow.amount)?;
+        escrow.status = EscrowStatus::Released;
+        storage::save_escrow(&env, escrow_id, &escrow);
+
+        env.events().publish(
+            (symbol_short!("escrow"), symbol_short!("released")),
+            (escrow.escrow_id, escrow.artist, escrow.amount),
+        );
+
+        Ok(())
+    }
+
+    /// Return a pending escrow to the tipper before its release time
+    pub fn refund_escrow(env: Env, escrow_id: String) -> Result<(), Error> {
+        let mut escrow = Self::get_escrow(env.clone(), escrow_id.clone())?;
+        escrow.tipper.require_auth();
+
+        if escrow.status != EscrowStatus::Pending {
+            return Err(Error::InvalidStatus);
+        }
+        if env.ledger().timestamp() >= escrow.release_time {
+            return Err(Error::ReleaseTimePassed);
+        }
+
+        transfer_out(&env, &escrow.asset, &escrow.tipper, escrow.amount)?;
+        escrow.status = EscrowStatus::Refunded;
+        storage::save_escrow(&env, escrow_id, &escrow);
+
+        env.events().publish(
+            (symbol_short!("escrow"), symbol_short!("refunded")),
+            (escrow.escrow_id, escrow.tipper, escrow.amount),
+        );
+
+        Ok(())
+    }
+
+    /// Freeze a pending escrow until the arbiter resolves it. Either the
+    /// tipper or the artist may open a dispute.
+    pub fn open_dispute(env: Env, caller: Address, escrow_id: String) -> Result<(), Error> {
+        caller.require_auth();
+        let mut escrow = Self::get_escrow(env.clone(), escrow_id.clone())?;
+
+        if caller != escrow.tipper && caller != escrow.artist {
+            return Err(Error::Unauthorized);
+        }
+        if escrow.status != EscrowStatus::Pending {
+            return Err(Error::InvalidStatus);
+        }
+
+        escrow.status = EscrowStatus::Disputed;
+        storage::save_escrow(&env, escrow_id, &escrow);
+
+        env.events().publish(
+            (symbol_short!("escrow"), symbol_short!("disputed")),
+            (escrow.escrow_id, caller),
+        );
+
+        Ok(())
+    }
+
+    /// Settle a disputed escrow (arbiter only). `artist_amount` goes to the
+    /// artist, split with collaborators as on release, and the remainder is
+    /// refunded to the tipper.
+    pub fn resolve_dispute(env: Env, escrow_id: String, artist_amount: i128) -> Result<(), Error> {
+        let arbiter = storage::get_arbiter(&env).ok_or(Error::NotInitialized)?;
+        arbiter.require_auth();
+        let mut escrow = Self::get_escrow(env.clone(), escrow_id.clone())?;
+
+        if escrow.status != EscrowStatus::Disputed {
+            return Err(Error::InvalidStatus);
+        }
+        if artist_amount < 0 || artist_amount > escrow.amount {
+            return Err(Error::InvalidAmount);
+        }
+        let tipper_amount = escrow.amount - artist_amount;
+
+        if artist_amount > 0 {
+            pay_artist(&env, &escrow, artist_amount)?;
+        }
+        if tipper_amount > 0 {
+            transfer_out(&env, &escrow.asset, &escrow.tipper, tipper_amount)?;
+        }
+
+        escrow.status = if tipper_amount == 0 {
+            EscrowStatus::Released
+        } else if artist_amount == 0 {
+            EscrowStatus::Refunded
+        } else {
+            EscrowStatus::Resolved
+        };
+        storage::save_escrow(&env, escrow_id, &escrow);
+
+        env.events().publish(
+            (symbol_short!("escrow"), symbol_short!("resolved")),
+            (escrow.escrow_id, artist_amount, tipper_amount),
+        );
+
+        Ok(())
+    }
+
+    /// Send a tip to an artist with optional royalty distribution.
+    /// Resubmitting with the same `idempotency_key` returns the original tip
+    /// id without moving funds again. Reusing a key for a different artist,
+    /// token or amount fails with `IdempotencyKeyReused`.
     pub fn send_tip(
         env: Env,
         sender: Address,
         artist: Address,
         token_address: Address,
         amount: i128,
+        idempotency_key: Option<BytesN<32>>,
+        metadata: Option<TipMetadata>,
     ) -> Result<u64, Error> {
         sender.require_auth();
 
+        let request_hash = tip_request_hash(&env, &artist, &token_address, amount);
+        if let Some(key) = &idempotency_key {
+            if let Some((tip_id, original_hash)) = storage::get_idempotent_tip(&env, &sender, key) {
+                if original_hash != request_hash {
+                    return Err(Error::IdempotencyKeyReused);
+                }
+                return Ok(tip_id);
+            }
+        }
+
         if amount <= 0 {
             return Err(Error::InvalidAmount);
         }
+        let metadata = validate_metadata(metadata)?;
 
         let token_client = token::Client::new(&env, &token_address);
-        let tip_id = env.ledger().sequence() as u64;
 
         // Check if artist has royalty splits configured
         if let Some(splits) = storage::get_splits(&env, &artist) {
@@ -134,13 +301,24 @@ impl TipEscrowContract {
         }
 
         // Record tip
+        let tip_id = storage::next_tip_id(&env);
         let tip = TipRecord {
+            tip_id,
             sender: sender.clone(),
             artist: artist.clone(),
             amount,
             timestamp: env.ledger().timestamp(),
+            metadata,
         };
-        storage::save_tip(&env, tip_id, &tip);
+        storage::save_tip(&env, &tip);
+        if let Some(key) = &idempotency_key {
+            storage::save_idempotent_tip(&env, &sender, key, tip_id, &request_hash);
+        }
+
+        env.events().publish(
+            (symbol_short!("tip"), symbol_short!("sent")),
+            (tip_id, sender, artist, amount, tip.metadata),
+        );
 
         Ok(tip_id)
     }
@@ -161,8 +339,123 @@ impl TipEscrowContract {
         storage::get_splits(&env, &artist)
     }
 
-    /// Get all tips
-    pub fn get_tips(env: Env) -> Vec<TipRecord> {
-        storage::get_tips(&env)
+    /// Get a tip by id
+    pub fn get_tip(env: Env, tip_id: u64) -> Option<TipRecord> {
+        storage::get_tip(&env, tip_id)
+    }
+
+    /// Get tips in the order they were sent, starting at `cursor`
+    pub fn get_tips_page(env: Env, cursor: u64, limit: u32) -> TipPage {
+        let total = storage::get_tip_count(&env);
+        collect_page(&env, cursor, limit, total, |position| {
+            storage::get_tip(&env, position + 1)
+        })
+    }
+
+    /// Get tips received by an artist, oldest first, starting at `cursor`
+    pub fn get_tips_by_artist(env: Env, artist: Address, cursor: u64, limit: u32) -> TipPage {
+        let total = storage::get_artist_tip_count(&env, &artist);
+        collect_page(&env, cursor, limit, total, |index| {
+            storage::get_artist_tip(&env, &artist, index)
+                .and_then(|tip_id| storage::get_tip(&env, tip_id))
+        })
+    }
+
+    /// Get tips sent by a sender, oldest first, starting at `cursor`
+    pub fn get_tips_by_sender(env: Env, sender: Address, cursor: u64, limit: u32) -> TipPage {
+        let total = storage::get_sender_tip_count(&env, &sender);
+        collect_page(&env, cursor, limit, total, |index| {
+            storage::get_sender_tip(&env, &sender, index)
+                .and_then(|tip_id| storage::get_tip(&env, tip_id))
+        })
+    }
+}
+
+/// Check attached tip details against their size limits
+fn validate_metadata(metadata: Option<TipMetadata>) -> Result<TipMetadata, Error> {
+    let metadata = metadata.unwrap_or_default();
+    let fields = [
+        (&metadata.message, MAX_MESSAGE_LEN),
+        (&metadata.track_id, MAX_TRACK_ID_LEN),
+        (&metadata.memo, MAX_MEMO_LEN),
+    ];
+    for (field, max_len) in fields {
+        if field.as_ref().is_some_and(|value| value.len() > max_len) {
+            return Err(Error::MetadataTooLong);
+        }
+    }
+    Ok(metadata)
+}
+
+/// Hash identifying what a `send_tip` call asked for, so a retried
+/// idempotency key can be checked against the original request.
+fn tip_request_hash(env: &Env, artist: &Address, token_address: &Address, amount: i128) -> BytesN<32> {
+    let request = (artist.clone(), token_address.clone(), amount);
+    env.crypto().sha256(&request.to_xdr(env)).into()
+}
+
+/// Read up to `limit` entries (capped at `MAX_PAGE_SIZE`) of a history with
+/// `total` entries, starting at `cursor`. An empty page never carries a
+/// cursor, so clients following `next_cursor` always make progress
+fn collect_page<F>(env: &Env, cursor: u64, limit: u32, total: u64, load: F) -> TipPage
+where
+    F: Fn(u64) -> Option<TipRecord>,
+{
+    let end = total.min(cursor.saturating_add(limit.min(MAX_PAGE_SIZE) as u64));
+    let mut tips = Vec::new(env);
+    let mut index = cursor;
+    while index < end {
+        if let Some(tip) = load(index) {
+            tips.push_back(tip);
+        }
+        index += 1;
+    }
+
+    TipPage {
+        tips,
+        next_cursor: if cursor < end && end < total { Some(end) } else { None },
+    }
+}
+
+/// Pay the artist's share of an escrow, distributing it according to the
+/// royalty splits snapshotted when the escrow was created
+fn pay_artist(env: &Env, escrow: &TipEscrow, amount: i128) -> Result<(), Error> {
+    let mut remaining = amount;
+
+    for split in escrow.splits.iter() {
+        let split_amount = amount
+            .checked_mul(split.percentage as i128)
+            .ok_or(Error::Overflow)?
+            .checked_div(10000)
+            .ok_or(Error::Overflow)?;
+        if split_amount > 0 {
+            transfer_out(env, &escrow.asset, &split.recipient, split_amount)?;
+            remaining = remaining
+                .checked_sub(split_amount)
+                .ok_or(Error::Underflow)?;
+        }
+    }
+
+    // Send remaining to artist
+    if remaining > 0 {
+        transfer_out(env, &escrow.asset, &escrow.artist, remaining)?;
     }
+
+    Ok(())
+}
+
+/// Token contract an escrow in `asset` is held in. Native XLM escrows use the
+/// Stellar Asset Contract passed to `initialize`.
+fn resolve_asset(env: &Env, asset: &Asset) -> Result<Address, Error> {
+    match asset {
+        Asset::Native => storage::get_native_token(env).ok_or(Error::NativeAssetNotConfigured),
+        Asset::Token(token_address) => Ok(token_address.clone()),
+    }
+}
+
+/// Send escrowed funds held by the contract to `to`
+fn transfer_out(env: &Env, asset: &Asset, to: &Address, amount: i128) -> Result<(), Error> {
+    let token_client = token::Client::new(env, &resolve_asset(env, asset)?);
+    token_client.transfer(&env.current_contract_address(), to, &amount);
+    Ok(())
 }
@@ -1,24 +1,91 @@
-use soroban_sdk::{contracttype, symbol_short, Address, Env, String, Symbol, Vec};
+use soroban_sdk::{contracttype, symbol_short, Address, BytesN, Env, String, Symbol, Vec};
 
 use crate::types::{RoyaltySplit, TipEscrow, TipRecord};
 
-const TIPS: Symbol = symbol_short!("TIPS");
 const SPLITS: Symbol = symbol_short!("SPLITS");
+const ADMIN: Symbol = symbol_short!("ADMIN");
+const ARBITER: Symbol = symbol_short!("ARBITER");
+const NATIVE: Symbol = symbol_short!("NATIVE");
 
 #[contracttype]
 #[derive(Clone)]
 pub enum DataKey {
     Escrow(String),
+    Tip(u64),
+    TipCount,
+    ArtistTip(Address, u64),
+    ArtistTipCount(Address),
+    SenderTip(Address, u64),
+    SenderTipCount(Address),
+    IdempotencyKey(Address, BytesN<32>), // -> (tip id, request hash)
 }
 
-pub fn save_tip(env: &Env, _tip_id: u64, tip: &TipRecord) {
-    let mut tips: Vec<TipRecord> = env.storage().instance().get(&TIPS).unwrap_or(Vec::new(env));
-    tips.push_back(tip.clone());
-    env.storage().instance().set(&TIPS, &tips);
+/// Id the next recorded tip will receive. Ids start at 1 and follow history
+/// order, so the tip at page position `n` has id `n + 1`.
+pub fn next_tip_id(env: &Env) -> u64 {
+    get_tip_count(env) + 1
 }
 
-pub fn get_tips(env: &Env) -> Vec<TipRecord> {
-    env.storage().instance().get(&TIPS).unwrap_or(Vec::new(env))
+/// Store a tip under its id and index it by artist and sender
+pub fn save_tip(env: &Env, tip: &TipRecord) {
+    env.storage().persistent().set(&DataKey::Tip(tip.tip_id), tip);
+    env.storage()
+        .instance()
+        .set(&DataKey::TipCount, &tip.tip_id);
+
+    let artist_count = get_artist_tip_count(env, &tip.artist);
+    env.storage().persistent().set(
+        &DataKey::ArtistTip(tip.artist.clone(), artist_count),
+        &tip.tip_id,
+    );
+    env.storage().persistent().set(
+        &DataKey::ArtistTipCount(tip.artist.clone()),
+        &(artist_count + 1),
+    );
+
+    let sender_count = get_sender_tip_count(env, &tip.sender);
+    env.storage().persistent().set(
+        &DataKey::SenderTip(tip.sender.clone(), sender_count),
+        &tip.tip_id,
+    );
+    env.storage().persistent().set(
+        &DataKey::SenderTipCount(tip.sender.clone()),
+        &(sender_count + 1),
+    );
+}
+
+pub fn get_tip(env: &Env, tip_id: u64) -> Option<TipRecord> {
+    env.storage().persistent().get(&DataKey::Tip(tip_id))
+}
+
+pub fn get_tip_count(env: &Env) -> u64 {
+    env.storage().instance().get(&DataKey::TipCount).unwrap_or(0)
+}
+
+pub fn get_artist_tip(env: &Env, artist: &Address, index: u64) -> Option<u64> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::ArtistTip(artist.clone(), index))
+}
+
+pub fn get_artist_tip_count(env: &Env, artist: &Address) -> u64 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::ArtistTipCount(artist.clone()))
+        .unwrap_or(0)
+}
+
+pub fn get_sender_tip(env: &Env, sender: &Address, index: u64) -> Option<u64> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::SenderTip(sender.clone(), index))
+}
+
+pub fn get_sender_tip_count(env: &Env, sender: &Address) -> u64 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::SenderTipCount(sender.clone()))
+        .unwrap_or(0)
 }
 
 pub fn save_splits(env: &Env, artist: &Address, splits: &Vec<RoyaltySplit>) {
@@ -38,3 +105,48 @@ pub fn save_escrow(env: &Env, escrow_id: String, escrow: &TipEscrow) {
 pub fn get_escrow(env: &Env, escrow_id: String) -> Option<TipEscrow> {
     env.storage().persistent().get(&DataKey::Escrow(escrow_id))
 }
+
+pub fn get_admin(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&ADMIN)
+}
+
+pub fn set_admin(env: &Env, admin: &Address) {
+    env.storage().instance().set(&ADMIN, admin);
+}
+
+pub fn get_arbiter(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&ARBITER)
+}
+
+pub fn set_arbiter(env: &Env, arbiter: &Address) {
+    env.storage().instance().set(&ARBITER, arbiter);
+}
+
+/// Tip recorded under a sender's idempotency key, with the hash of the
+/// request that created it.
+pub fn get_idempotent_tip(env: &Env, sender: &Address, key: &BytesN<32>) -> Option<(u64, BytesN<32>)> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::IdempotencyKey(sender.clone(), key.clone()))
+}
+
+pub fn save_idempotent_tip(
+    env: &Env,
+    sender: &Address,
+    key: &BytesN<32>,
+    tip_id: u64,
+    request_hash: &BytesN<32>,
+) {
+    env.storage().persistent().set(
+        &DataKey::IdempotencyKey(sender.clone(), key.clone()),
+        &(tip_id, request_hash.clone()),
+    );
+}
+
+pub fn get_native_token(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&NATIVE)
+}
+
+pub fn set_native_token(env: &Env, native_token: &Address) {
+    env.storage().instance().set(&NATIVE, native_token);
+}
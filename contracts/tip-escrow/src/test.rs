@@ -1,7 +1,10 @@
 #![cfg(test)]
 
 use super::*;
-use soroban_sdk::{testutils::Address as _, token, Address, Env, Vec};
+use soroban_sdk::{
+    testutils::{Address as _, Events, Ledger},
+    token, Address, BytesN, Env, IntoVal, String, Vec,
+};
 
 fn create_token_contract<'a>(
     env: &Env,
@@ -29,7 +32,7 @@ fn test_send_tip_without_splits() {
     let (token, token_admin) = create_token_contract(&env, &admin);
     token_admin.mint(&sender, &1000);
 
-    let tip_id = client.send_tip(&sender, &artist, &token.address, &100);
+    let tip_id = client.send_tip(&sender, &artist, &token.address, &100, &None, &None);
 
     assert_eq!(token.balance(&artist), 100);
     assert_eq!(token.balance(&sender), 900);
@@ -60,7 +63,7 @@ fn test_send_tip_with_splits() {
     });
     client.set_royalty_splits(&artist, &splits);
 
-    client.send_tip(&sender, &artist, &token.address, &100);
+    client.send_tip(&sender, &artist, &token.address, &100, &None, &None);
 
     assert_eq!(token.balance(&collaborator), 20);
     assert_eq!(token.balance(&artist), 80);
@@ -136,7 +139,7 @@ fn test_create_and_get_escrow() {
     let release_time = env.ledger().timestamp() + 100;
 
     let amount = 200;
-    let escrow_id = client.create_escrow(&tipper, &artist, &amount, &asset, &release_time);
+    let escrow_id = client.create_escrow(&tipper, &artist, &amount, &asset, &release_time, &None);
 
     assert_eq!(token.balance(&tipper), 800);
     assert_eq!(token.balance(&contract_id), 200);
@@ -146,3 +149,441 @@ fn test_create_and_get_escrow() {
     assert_eq!(escrow.artist, artist);
     assert_eq!(escrow.amount, amount);
 }
+
+struct EscrowSetup<'a> {
+    env: Env,
+    client: TipEscrowContractClient<'a>,
+    token: token::Client<'a>,
+    tipper: Address,
+    artist: Address,
+    escrow_id: String,
+}
+
+fn setup_escrow<'a>() -> EscrowSetup<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, TipEscrowContract);
+    let client = TipEscrowContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let arbiter = Address::generate(&env);
+    let tipper = Address::generate(&env);
+    let artist = Address::generate(&env);
+
+    let (token, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&tipper, &1000);
+    client.initialize(&admin, &arbiter, &token.address);
+
+    let asset = types::Asset::Token(token.address.clone());
+    let release_time = env.ledger().timestamp() + 100;
+    let escrow_id = client.create_escrow(&tipper, &artist, &200, &asset, &release_time, &None);
+
+    EscrowSetup {
+        env,
+        client,
+        token,
+        tipper,
+        artist,
+        escrow_id,
+    }
+}
+
+fn advance_time(env: &Env, seconds: u64) {
+    env.ledger().with_mut(|li| li.timestamp += seconds);
+}
+
+#[test]
+fn test_release_escrow_after_release_time() {
+    let s = setup_escrow();
+
+    assert_eq!(
+        s.client.try_release_escrow(&s.escrow_id),
+        Err(Ok(Error::ReleaseTimeNotReached))
+    );
+
+    advance_time(&s.env, 100);
+    s.client.release_escrow(&s.escrow_id);
+
+    assert_eq!(s.token.balance(&s.artist), 200);
+    assert_eq!(s.client.get_escrow(&s.escrow_id).status, EscrowStatus::Released);
+    assert_eq!(
+        s.client.try_release_escrow(&s.escrow_id),
+        Err(Ok(Error::InvalidStatus))
+    );
+}
+
+#[test]
+fn test_refund_escrow_before_release_time() {
+    let s = setup_escrow();
+
+    s.client.refund_escrow(&s.escrow_id);
+
+    assert_eq!(s.token.balance(&s.tipper), 1000);
+    assert_eq!(s.client.get_escrow(&s.escrow_id).status, EscrowStatus::Refunded);
+}
+
+#[test]
+fn test_refund_escrow_after_release_time_fails() {
+    let s = setup_escrow();
+    advance_time(&s.env, 100);
+
+    assert_eq!(
+        s.client.try_refund_escrow(&s.escrow_id),
+        Err(Ok(Error::ReleaseTimePassed))
+    );
+}
+
+#[test]
+fn test_dispute_blocks_release_and_refund() {
+    let s = setup_escrow();
+    let stranger = Address::generate(&s.env);
+
+    assert_eq!(
+        s.client.try_open_dispute(&stranger, &s.escrow_id),
+        Err(Ok(Error::Unauthorized))
+    );
+
+    s.client.open_dispute(&s.artist, &s.escrow_id);
+    assert_eq!(s.client.get_escrow(&s.escrow_id).status, EscrowStatus::Disputed);
+
+    assert_eq!(
+        s.client.try_refund_escrow(&s.escrow_id),
+        Err(Ok(Error::InvalidStatus))
+    );
+    advance_time(&s.env, 100);
+    assert_eq!(
+        s.client.try_release_escrow(&s.escrow_id),
+        Err(Ok(Error::InvalidStatus))
+    );
+}
+
+#[test]
+fn test_resolve_dispute_with_split() {
+    let s = setup_escrow();
+
+    assert_eq!(
+        s.client.try_resolve_dispute(&s.escrow_id, &50),
+        Err(Ok(Error::InvalidStatus))
+    );
+
+    s.client.open_dispute(&s.tipper, &s.escrow_id);
+    assert_eq!(
+        s.client.try_resolve_dispute(&s.escrow_id, &201),
+        Err(Ok(Error::InvalidAmount))
+    );
+
+    s.client.resolve_dispute(&s.escrow_id, &50);
+
+    assert_eq!(s.token.balance(&s.artist), 50);
+    assert_eq!(s.token.balance(&s.tipper), 950);
+    assert_eq!(s.client.get_escrow(&s.escrow_id).status, EscrowStatus::Resolved);
+}
+
+#[test]
+fn test_resolve_dispute_full_refund() {
+    let s = setup_escrow();
+
+    s.client.open_dispute(&s.tipper, &s.escrow_id);
+    s.client.resolve_dispute(&s.escrow_id, &0);
+
+    assert_eq!(s.token.balance(&s.tipper), 1000);
+    assert_eq!(s.client.get_escrow(&s.escrow_id).status, EscrowStatus::Refunded);
+}
+
+#[test]
+fn test_release_escrow_uses_splits_from_creation() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, TipEscrowContract);
+    let client = TipEscrowContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let tipper = Address::generate(&env);
+    let artist = Address::generate(&env);
+    let collaborator = Address::generate(&env);
+    let newcomer = Address::generate(&env);
+
+    let (token, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&tipper, &1000);
+
+    let mut splits = Vec::new(&env);
+    splits.push_back(RoyaltySplit {
+        recipient: collaborator.clone(),
+        percentage: 2500, // 25%
+    });
+    client.set_royalty_splits(&artist, &splits);
+
+    let asset = types::Asset::Token(token.address.clone());
+    let release_time = env.ledger().timestamp() + 100;
+    let escrow_id = client.create_escrow(&tipper, &artist, &200, &asset, &release_time, &None);
+
+    // Splits changed after creation must not affect this escrow
+    let mut new_splits = Vec::new(&env);
+    new_splits.push_back(RoyaltySplit {
+        recipient: newcomer.clone(),
+        percentage: 5000,
+    });
+    client.set_royalty_splits(&artist, &new_splits);
+
+    advance_time(&env, 100);
+    client.release_escrow(&escrow_id);
+
+    assert_eq!(token.balance(&collaborator), 50);
+    assert_eq!(token.balance(&artist), 150);
+    assert_eq!(token.balance(&newcomer), 0);
+    assert_eq!(client.get_escrow(&escrow_id).splits, splits);
+}
+
+#[test]
+fn test_tip_history_pagination() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, TipEscrowContract);
+    let client = TipEscrowContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let artist_a = Address::generate(&env);
+    let artist_b = Address::generate(&env);
+
+    let (token, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&alice, &1000);
+    token_admin.mint(&bob, &1000);
+
+    client.send_tip(&alice, &artist_a, &token.address, &10, &None, &None);
+    client.send_tip(&bob, &artist_a, &token.address, &20, &None, &None);
+    client.send_tip(&alice, &artist_b, &token.address, &30, &None, &None);
+    client.send_tip(&alice, &artist_a, &token.address, &40, &None, &None);
+    client.send_tip(&bob, &artist_b, &token.address, &50, &None, &None);
+
+    let first = client.get_tips_page(&0, &2);
+    assert_eq!(first.tips.len(), 2);
+    assert_eq!(first.tips.get(0).unwrap().amount, 10);
+    assert_eq!(first.next_cursor, Some(2));
+
+    let second = client.get_tips_page(&2, &2);
+    assert_eq!(second.tips.get(0).unwrap().amount, 30);
+    assert_eq!(second.next_cursor, Some(4));
+
+    let last = client.get_tips_page(&4, &2);
+    assert_eq!(last.tips.len(), 1);
+    assert_eq!(last.tips.get(0).unwrap().amount, 50);
+    assert_eq!(last.next_cursor, None);
+
+    let by_artist = client.get_tips_by_artist(&artist_a, &0, &10);
+    assert_eq!(by_artist.tips.len(), 3);
+    assert_eq!(by_artist.tips.get(2).unwrap().amount, 40);
+    assert_eq!(by_artist.next_cursor, None);
+
+    let by_sender = client.get_tips_by_sender(&bob, &1, &10);
+    assert_eq!(by_sender.tips.len(), 1);
+    assert_eq!(by_sender.tips.get(0).unwrap().amount, 50);
+
+    assert_eq!(client.get_tips_page(&10, &5).tips.len(), 0);
+
+    // A zero limit must not hand back a cursor that loops forever
+    let empty = client.get_tips_page(&0, &0);
+    assert_eq!(empty.tips.len(), 0);
+    assert_eq!(empty.next_cursor, None);
+}
+
+#[test]
+fn test_tip_ids_are_unique_within_a_ledger() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, TipEscrowContract);
+    let client = TipEscrowContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let sender = Address::generate(&env);
+    let artist = Address::generate(&env);
+
+    let (token, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&sender, &1000);
+
+    let first = client.send_tip(&sender, &artist, &token.address, &100, &None, &None);
+    let second = client.send_tip(&sender, &artist, &token.address, &200, &None, &None);
+
+    assert_eq!(first, 1);
+    assert_eq!(second, 2);
+    assert_eq!(client.get_tip(&first).unwrap().amount, 100);
+    assert_eq!(client.get_tip(&second).unwrap().amount, 200);
+
+    let event = env.events().all().last().unwrap();
+    assert_eq!(event.0, contract_id);
+    assert_eq!(
+        event.1,
+        (symbol_short!("tip"), symbol_short!("sent")).into_val(&env)
+    );
+}
+
+#[test]
+fn test_send_tip_is_idempotent() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, TipEscrowContract);
+    let client = TipEscrowContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let sender = Address::generate(&env);
+    let artist = Address::generate(&env);
+
+    let (token, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&sender, &1000);
+
+    let key = Some(BytesN::from_array(&env, &[7; 32]));
+    let tip_id = client.send_tip(&sender, &artist, &token.address, &100, &key, &None);
+    let retried = client.send_tip(&sender, &artist, &token.address, &100, &key, &None);
+
+    assert_eq!(retried, tip_id);
+    assert_eq!(token.balance(&artist), 100);
+    assert_eq!(client.get_tips_page(&0, &10).tips.len(), 1);
+
+    // Reusing the key for a different tip is rejected rather than deduplicated
+    let other_artist = Address::generate(&env);
+    assert_eq!(
+        client.try_send_tip(&sender, &other_artist, &token.address, &100, &key, &None),
+        Err(Ok(Error::IdempotencyKeyReused))
+    );
+    assert_eq!(
+        client.try_send_tip(&sender, &artist, &token.address, &250, &key, &None),
+        Err(Ok(Error::IdempotencyKeyReused))
+    );
+    assert_eq!(token.balance(&sender), 900);
+
+    // The same key from another sender is a different submission
+    let other = Address::generate(&env);
+    token_admin.mint(&other, &1000);
+    let other_tip = client.send_tip(&other, &artist, &token.address, &100, &key, &None);
+    assert_ne!(other_tip, tip_id);
+    assert_eq!(token.balance(&artist), 200);
+}
+
+#[test]
+fn test_tip_metadata_is_stored() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, TipEscrowContract);
+    let client = TipEscrowContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let sender = Address::generate(&env);
+    let artist = Address::generate(&env);
+
+    let (token, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&sender, &1000);
+
+    let metadata = types::TipMetadata {
+        message: Some(String::from_str(&env, "Loved the show!")),
+        track_id: Some(String::from_str(&env, "track-42")),
+        memo: None,
+    };
+
+    let tip_id = client.send_tip(
+        &sender,
+        &artist,
+        &token.address,
+        &100,
+        &None,
+        &Some(metadata.clone()),
+    );
+    assert_eq!(client.get_tip(&tip_id).unwrap().metadata, metadata);
+
+    let asset = types::Asset::Token(token.address.clone());
+    let release_time = env.ledger().timestamp() + 100;
+    let escrow_id = client.create_escrow(
+        &sender,
+        &artist,
+        &200,
+        &asset,
+        &release_time,
+        &Some(metadata.clone()),
+    );
+    assert_eq!(client.get_escrow(&escrow_id).metadata, metadata);
+
+    let plain = client.send_tip(&sender, &artist, &token.address, &100, &None, &None);
+    assert_eq!(
+        client.get_tip(&plain).unwrap().metadata,
+        types::TipMetadata::default()
+    );
+}
+
+#[test]
+fn test_tip_metadata_too_long() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, TipEscrowContract);
+    let client = TipEscrowContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let sender = Address::generate(&env);
+    let artist = Address::generate(&env);
+
+    let (token, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&sender, &1000);
+
+    let metadata = types::TipMetadata {
+        message: None,
+        track_id: None,
+        memo: Some(String::from_bytes(&env, &[b'x'; 65])),
+    };
+
+    assert_eq!(
+        client.try_send_tip(&sender, &artist, &token.address, &100, &None, &Some(metadata)),
+        Err(Ok(Error::MetadataTooLong))
+    );
+    assert_eq!(token.balance(&sender), 1000);
+}
+
+#[test]
+fn test_native_escrow_uses_configured_asset_contract() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, TipEscrowContract);
+    let client = TipEscrowContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let tipper = Address::generate(&env);
+    let artist = Address::generate(&env);
+    let release_time = env.ledger().timestamp() + 100;
+
+    assert_eq!(
+        client.try_create_escrow(
+            &tipper,
+            &artist,
+            &200,
+            &types::Asset::Native,
+            &release_time,
+            &None
+        ),
+        Err(Ok(Error::NativeAssetNotConfigured))
+    );
+
+    // Stand-in for the XLM Stellar Asset Contract
+    let (xlm, xlm_admin) = create_token_contract(&env, &admin);
+    xlm_admin.mint(&tipper, &1000);
+    client.initialize(&admin, &Address::generate(&env), &xlm.address);
+
+    let escrow_id = client.create_escrow(
+        &tipper,
+        &artist,
+        &200,
+        &types::Asset::Native,
+        &release_time,
+        &None,
+    );
+    assert_eq!(xlm.balance(&contract_id), 200);
+
+    advance_time(&env, 100);
+    client.release_escrow(&escrow_id);
+    assert_eq!(xlm.balance(&artist), 200);
+}
@@ -1,12 +1,32 @@
-use soroban_sdk::{contracterror, contracttype, Address, String};
+use soroban_sdk::{contracterror, contracttype, Address, String, Vec};
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct TipRecord {
+    pub tip_id: u64,
     pub sender: Address,
     pub artist: Address,
     pub amount: i128,
     pub timestamp: u64,
+    pub metadata: TipMetadata,
+}
+
+/// Optional details a fan can attach to a tip
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct TipMetadata {
+    pub message: Option<String>,  // Up to 280 bytes
+    pub track_id: Option<String>, // Up to 64 bytes
+    pub memo: Option<String>,     // Up to 64 bytes, e.g. an external reference
+}
+
+/// One page of tip history. `next_cursor` is the cursor to pass for the
+/// following page, or `None` when there are no more tips.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TipPage {
+    pub tips: Vec<TipRecord>,
+    pub next_cursor: Option<u64>,
 }
 
 #[contracttype]
@@ -24,11 +44,21 @@ pub enum Error {
     EscrowNotFound = 2,
     Overflow = 3,         // Amount overflow in distribution
     Underflow = 4,        // Amount underflow in distribution
+    Unauthorized = 5,
+    InvalidStatus = 6,    // Escrow is not in a state that allows the action
+    ReleaseTimeNotReached = 7,
+    ReleaseTimePassed = 8,
+    NotInitialized = 9,
+    AlreadyInitialized = 10,
+    MetadataTooLong = 11,
+    NativeAssetNotConfigured = 12,
+    IdempotencyKeyReused = 13, // Key already used for a different tip
 }
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum Asset {
+    Native, // XLM, escrowed through the native token set at initialization
     Token(Address),
 }
 
@@ -43,6 +73,8 @@ pub struct TipEscrow {
     pub status: EscrowStatus,
     pub release_time: u64,
     pub created_at: u64,
+    pub splits: Vec<RoyaltySplit>, // Artist's royalty splits when the escrow was created
+    pub metadata: TipMetadata,
 }
 
 #[contracttype]
@@ -52,6 +84,7 @@ pub enum EscrowStatus {
     Released,
     Refunded,
     Disputed,
+    Resolved, // Dispute settled with funds split between artist and tipper
 }
 
 // #[contracttype]
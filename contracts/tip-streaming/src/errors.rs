@@ -18,4 +18,5 @@ pub enum Error {
     RateTooHigh           = 12,
     BalanceBelowMinimum   = 13,
     StreamExpired         = 14,
+    InvalidSplit          = 15,
 }
\ No newline at end of file
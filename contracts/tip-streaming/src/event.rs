@@ -10,7 +10,7 @@ pub fn stream_started(
 ) {
     env.events().publish(
         (symbol_short!("STREAM"), symbol_short!("START")),
-        (stream_id, listener, artist, rate_per_second, deposited_amount),
+        (stream_id.clone(), listener.clone(), artist.clone(), rate_per_second, deposited_amount),
     );
 }
 
@@ -24,7 +24,7 @@ pub fn stream_stopped(
 ) {
     env.events().publish(
         (symbol_short!("STREAM"), symbol_short!("STOP")),
-        (stream_id, listener, artist, amount_paid, refunded),
+        (stream_id.clone(), listener.clone(), artist.clone(), amount_paid, refunded),
     );
 }
 
@@ -37,7 +37,21 @@ pub fn stream_interrupted(
 ) {
     env.events().publish(
         (symbol_short!("STREAM"), symbol_short!("INTR")),
-        (stream_id, listener, artist, amount_paid),
+        (stream_id.clone(), listener.clone(), artist.clone(), amount_paid),
+    );
+}
+
+pub fn stream_expired(
+    env: &Env,
+    stream_id: &String,
+    listener: &Address,
+    artist: &Address,
+    amount_paid: i128,
+    refunded: i128,
+) {
+    env.events().publish(
+        (symbol_short!("STREAM"), symbol_short!("EXPIRE")),
+        (stream_id.clone(), listener.clone(), artist.clone(), amount_paid, refunded),
     );
 }
 
@@ -49,6 +63,6 @@ pub fn payment_settled(
 ) {
     env.events().publish(
         (symbol_short!("STREAM"), symbol_short!("PAY")),
-        (stream_id, artist, amount),
+        (stream_id.clone(), artist.clone(), amount),
     );
-}
\ No newline at end of file
+}
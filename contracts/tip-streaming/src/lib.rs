@@ -1,14 +1,470 @@
-pub fn add(left: u64, right: u64) -> u64 {
-    left + right
+#![no_std]
+
+pub mod errors;
+pub mod event;
+pub mod storage;
+pub mod types;
+
+use soroban_sdk::{contract, contractimpl, token, vec, Address, Env, String, Vec};
+
+pub use errors::Error;
+pub use types::{StreamConfig, StreamMode, StreamRecipient, StreamRecord, StreamStatus};
+
+const MAX_FEE_BASIS_POINTS: u32 = 1_000; // 10%
+const BASIS_POINTS: i128 = 10_000;
+const MAX_PLAYLIST_ARTISTS: u32 = 20;
+
+#[contract]
+pub struct TipStreamingContract;
+
+#[contractimpl]
+impl TipStreamingContract {
+    #[allow(clippy::too_many_arguments)]
+    pub fn initialize(
+        env: Env,
+        admin: Address,
+        token: Address,
+        fee_collector: Address,
+        fee_basis_points: u32,
+        min_rate_per_second: i128,
+        max_rate_per_second: i128,
+        min_deposit: i128,
+    ) -> Result<(), Error> {
+        if storage::get_config(&env).is_some() {
+            return Err(Error::AlreadyInitialized);
+        }
+        admin.require_auth();
+
+        if fee_basis_points > MAX_FEE_BASIS_POINTS {
+            return Err(Error::InvalidAmount);
+        }
+        if min_rate_per_second <= 0 || max_rate_per_second < min_rate_per_second {
+            return Err(Error::InvalidRate);
+        }
+        if min_deposit < 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        let config = StreamConfig {
+            admin,
+            token,
+            fee_collector,
+            fee_basis_points,
+            min_rate_per_second,
+            max_rate_per_second,
+            min_deposit,
+        };
+        storage::set_config(&env, &config);
+        Ok(())
+    }
+
+    /// Open a pay-as-you-listen stream from `listener` to `artist`, escrowing
+    /// `deposit` in the contract. Only one stream may be active per pair.
+    pub fn start_stream(
+        env: Env,
+        listener: Address,
+        artist: Address,
+        rate_per_second: i128,
+        deposit: i128,
+    ) -> Result<String, Error> {
+        listener.require_auth();
+        let config = storage::get_config(&env).ok_or(Error::NotInitialized)?;
+
+        let recipients = vec![
+            &env,
+            StreamRecipient {
+                artist,
+                weight_basis_points: BASIS_POINTS as u32,
+                amount_paid: 0,
+            },
+        ];
+        open_stream(&env, &config, listener, recipients, StreamMode::Single, rate_per_second, deposit)
+    }
+
+    /// Open one stream that pays a weighted set of artists at a combined
+    /// `rate_per_second`, e.g. for a playlist session. Weights are in basis
+    /// points and must total 10,000. Each listener/artist pair in the set
+    /// counts towards the one-active-stream limit.
+    pub fn start_playlist_stream(
+        env: Env,
+        listener: Address,
+        artists: Vec<(Address, u32)>,
+        rate_per_second: i128,
+        deposit: i128,
+    ) -> Result<String, Error> {
+        listener.require_auth();
+        let config = storage::get_config(&env).ok_or(Error::NotInitialized)?;
+
+        if artists.is_empty() || artists.len() > MAX_PLAYLIST_ARTISTS {
+            return Err(Error::InvalidSplit);
+        }
+        let mut recipients = Vec::new(&env);
+        let mut total_bp: u32 = 0;
+        for (artist, weight_basis_points) in artists.iter() {
+            if weight_basis_points == 0 || weight_basis_points > BASIS_POINTS as u32 {
+                return Err(Error::InvalidSplit);
+            }
+            if recipients.iter().any(|r: StreamRecipient| r.artist == artist) {
+                return Err(Error::InvalidSplit);
+            }
+            total_bp += weight_basis_points;
+            recipients.push_back(StreamRecipient {
+                artist,
+                weight_basis_points,
+                amount_paid: 0,
+            });
+        }
+        if total_bp != BASIS_POINTS as u32 {
+            return Err(Error::InvalidSplit);
+        }
+
+        open_stream(&env, &config, listener, recipients, StreamMode::Playlist, rate_per_second, deposit)
+    }
+
+    /// Pay the artist for listening time since the last settlement, net of the
+    /// protocol fee. Callable by anyone. Returns the net amount paid out.
+    ///
+    /// If the remaining deposit can no longer cover the rate, the stream is
+    /// settled up to its depletion time and closed as `Expired`.
+    pub fn settle(env: Env, stream_id: String) -> Result<i128, Error> {
+        let config = storage::get_config(&env).ok_or(Error::NotInitialized)?;
+        let mut record = storage::get_stream(&env, &stream_id).ok_or(Error::StreamNotFound)?;
+        ensure_active(&record)?;
+
+        let net = settle_accrued(&env, &config, &mut record);
+        storage::set_stream(&env, &stream_id, &record);
+        Ok(net)
+    }
+
+    /// Add funds to an active stream. Returns the amount added.
+    ///
+    /// A stream that has already run dry cannot be revived: it is settled and
+    /// closed as `Expired` instead, nothing is taken from the listener and `0`
+    /// is returned. Start a new stream to keep listening.
+    pub fn top_up(env: Env, stream_id: String, amount: i128) -> Result<i128, Error> {
+        let config = storage::get_config(&env).ok_or(Error::NotInitialized)?;
+        let mut record = storage::get_stream(&env, &stream_id).ok_or(Error::StreamNotFound)?;
+        record.listener.require_auth();
+
+        ensure_active(&record)?;
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        if env.ledger().timestamp() >= depletion_time(&record) {
+            settle_accrued(&env, &config, &mut record);
+            storage::set_stream(&env, &stream_id, &record);
+            return Ok(0);
+        }
+
+        let token_client = token::Client::new(&env, &config.token);
+        if token_client.balance(&record.listener) < amount {
+            return Err(Error::InsufficientBalance);
+        }
+        token_client.transfer(&record.listener, &env.current_contract_address(), &amount);
+
+        record.deposited_amount += amount;
+        storage::set_stream(&env, &stream_id, &record);
+        Ok(amount)
+    }
+
+    /// Settle what is owed, refund the unused deposit to the listener and close
+    /// the stream. Returns the refunded amount.
+    pub fn stop_stream(env: Env, stream_id: String) -> Result<i128, Error> {
+        let config = storage::get_config(&env).ok_or(Error::NotInitialized)?;
+        let mut record = storage::get_stream(&env, &stream_id).ok_or(Error::StreamNotFound)?;
+        record.listener.require_auth();
+
+        ensure_active(&record)?;
+        let refund = finish_stream(&env, &config, &mut record, StreamStatus::Stopped);
+        storage::set_stream(&env, &stream_id, &record);
+        Ok(refund)
+    }
+
+    /// Close a stream because playback stopped. Permissionless so that a player
+    /// backend can call it: the artist is paid for the time listened and the
+    /// rest of the deposit goes back to the listener. Returns the refund.
+    pub fn interrupt_stream(env: Env, stream_id: String) -> Result<i128, Error> {
+        let config = storage::get_config(&env).ok_or(Error::NotInitialized)?;
+        let mut record = storage::get_stream(&env, &stream_id).ok_or(Error::StreamNotFound)?;
+
+        ensure_active(&record)?;
+        let refund = finish_stream(&env, &config, &mut record, StreamStatus::Interrupted);
+        storage::set_stream(&env, &stream_id, &record);
+        Ok(refund)
+    }
+
+    pub fn get_config(env: Env) -> Result<StreamConfig, Error> {
+        storage::get_config(&env).ok_or(Error::NotInitialized)
+    }
+
+    /// A stream that has run dry but was not settled since is reported as
+    /// `Expired` at its depletion time. Its amounts stay as of the last
+    /// settlement until the next `settle`, `stop_stream`, `interrupt_stream`
+    /// or `top_up` pays it out.
+    pub fn get_stream(env: Env, stream_id: String) -> Result<StreamRecord, Error> {
+        let mut record = storage::get_stream(&env, &stream_id).ok_or(Error::StreamNotFound)?;
+        if record.status == StreamStatus::Active {
+            let depleted_at = depletion_time(&record);
+            if env.ledger().timestamp() >= depleted_at {
+                record.status = StreamStatus::Expired;
+                record.stopped_at = Some(depleted_at);
+            }
+        }
+        Ok(record)
+    }
+
+    /// The listener's stream to `artist`, unless it has run dry.
+    pub fn get_active_stream(env: Env, listener: Address, artist: Address) -> Option<String> {
+        let stream_id = storage::get_active_stream(&env, &listener, &artist)?;
+        let record = storage::get_stream(&env, &stream_id)?;
+        if env.ledger().timestamp() >= depletion_time(&record) {
+            return None;
+        }
+        Some(stream_id)
+    }
+
+    /// Amount the artist could be paid right now, before fees.
+    pub fn get_accrued(env: Env, stream_id: String) -> Result<i128, Error> {
+        let record = storage::get_stream(&env, &stream_id).ok_or(Error::StreamNotFound)?;
+        if record.status != StreamStatus::Active {
+            return Ok(0);
+        }
+        let until = env.ledger().timestamp().min(depletion_time(&record));
+        Ok(record.rate_per_second * (until - record.last_settled_at) as i128)
+    }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+fn open_stream(
+    env: &Env,
+    config: &StreamConfig,
+    listener: Address,
+    recipients: Vec<StreamRecipient>,
+    mode: StreamMode,
+    rate_per_second: i128,
+    deposit: i128,
+) -> Result<String, Error> {
+    if recipients.iter().any(|r| r.artist == listener) {
+        return Err(Error::SameListenerArtist);
+    }
+    if rate_per_second <= 0 {
+        return Err(Error::InvalidRate);
+    }
+    if rate_per_second < config.min_rate_per_second {
+        return Err(Error::RateTooLow);
+    }
+    if rate_per_second > config.max_rate_per_second {
+        return Err(Error::RateTooHigh);
+    }
+    if deposit <= 0 || deposit < config.min_deposit {
+        return Err(Error::InvalidAmount);
+    }
+    if deposit < rate_per_second {
+        return Err(Error::BalanceBelowMinimum);
+    }
+    for recipient in recipients.iter() {
+        if let Some(active_id) = storage::get_active_stream(env, &listener, &recipient.artist) {
+            // A depleted stream no longer blocks the pair: expire it first.
+            let mut active = storage::get_stream(env, &active_id).ok_or(Error::StreamNotFound)?;
+            if env.ledger().timestamp() < depletion_time(&active) {
+                return Err(Error::StreamAlreadyActive);
+            }
+            settle_accrued(env, config, &mut active);
+            storage::set_stream(env, &active_id, &active);
+        }
+    }
 
-    #[test]
-    fn it_works() {
-        let result = add(2, 2);
-        assert_eq!(result, 4);
+    let token_client = token::Client::new(env, &config.token);
+    if token_client.balance(&listener) < deposit {
+        return Err(Error::InsufficientBalance);
     }
+    token_client.transfer(&listener, &env.current_contract_address(), &deposit);
+
+    let count = storage::increment_stream_count(env);
+    let stream_id = stream_id_from_count(env, count);
+    let now = env.ledger().timestamp();
+    let artist = recipients.get_unchecked(0).artist;
+
+    let record = StreamRecord {
+        stream_id: stream_id.clone(),
+        listener: listener.clone(),
+        artist: artist.clone(),
+        rate_per_second,
+        deposited_amount: deposit,
+        amount_paid: 0,
+        started_at: now,
+        stopped_at: None,
+        last_settled_at: now,
+        status: StreamStatus::Active,
+        mode,
+        recipients: recipients.clone(),
+    };
+    storage::set_stream(env, &stream_id, &record);
+    for recipient in recipients.iter() {
+        storage::set_active_stream(env, &listener, &recipient.artist, &stream_id);
+    }
+
+    event::stream_started(env, &stream_id, &listener, &artist, rate_per_second, deposit);
+    Ok(stream_id)
 }
+
+fn ensure_active(record: &StreamRecord) -> Result<(), Error> {
+    match record.status {
+        StreamStatus::Active => Ok(()),
+        StreamStatus::Expired => Err(Error::StreamExpired),
+        StreamStatus::Stopped | StreamStatus::Interrupted => Err(Error::StreamAlreadyStopped),
+    }
+}
+
+/// Timestamp after which the remaining deposit can no longer cover a full
+/// second at the stream's rate.
+fn depletion_time(record: &StreamRecord) -> u64 {
+    let remaining = record.deposited_amount - record.amount_paid;
+    record.last_settled_at + (remaining / record.rate_per_second) as u64
+}
+
+/// Settle an active stream up to now, expiring it if it has run dry. Returns
+/// the net amount sent to the artist.
+fn settle_accrued(env: &Env, config: &StreamConfig, record: &mut StreamRecord) -> i128 {
+    let now = env.ledger().timestamp();
+    let depleted_at = depletion_time(record);
+    let net = settle_until(env, config, record, now.min(depleted_at));
+    if now >= depleted_at {
+        close_stream(env, config, record, StreamStatus::Expired, depleted_at);
+    }
+    net
+}
+
+/// Settle and close an active stream with `status`, unless it already ran
+/// dry, in which case it is closed as `Expired` at its depletion time.
+/// Returns the amount refunded to the listener.
+fn finish_stream(
+    env: &Env,
+    config: &StreamConfig,
+    record: &mut StreamRecord,
+    status: StreamStatus,
+) -> i128 {
+    let now = env.ledger().timestamp();
+    let depleted_at = depletion_time(record);
+    let (status, closed_at) = if now >= depleted_at {
+        (StreamStatus::Expired, depleted_at)
+    } else {
+        (status, now)
+    };
+    settle_until(env, config, record, closed_at);
+    close_stream(env, config, record, status, closed_at)
+}
+
+/// Pay out `rate * (until - last_settled_at)` across the stream's recipients
+/// by weight, net of fees, and advance the settlement point. The last
+/// recipient absorbs rounding. Returns the total net amount paid out.
+fn settle_until(env: &Env, config: &StreamConfig, record: &mut StreamRecord, until: u64) -> i128 {
+    let gross = record.rate_per_second * (until - record.last_settled_at) as i128;
+    record.last_settled_at = until;
+    if gross <= 0 {
+        return 0;
+    }
+
+    let token_client = token::Client::new(env, &config.token);
+    let contract_address = env.current_contract_address();
+    let count = record.recipients.len();
+    let mut distributed = 0;
+    let mut total_fee = 0;
+    let mut total_net = 0;
+
+    for i in 0..count {
+        let mut recipient = record.recipients.get_unchecked(i);
+        let share = if i == count - 1 {
+            gross - distributed
+        } else {
+            gross * recipient.weight_basis_points as i128 / BASIS_POINTS
+        };
+        distributed += share;
+        if share <= 0 {
+            continue;
+        }
+
+        let fee = share * config.fee_basis_points as i128 / BASIS_POINTS;
+        let net = share - fee;
+        token_client.transfer(&contract_address, &recipient.artist, &net);
+        total_fee += fee;
+        total_net += net;
+
+        recipient.amount_paid += share;
+        event::payment_settled(env, &record.stream_id, &recipient.artist, net);
+        record.recipients.set(i, recipient);
+    }
+
+    if total_fee > 0 {
+        token_client.transfer(&contract_address, &config.fee_collector, &total_fee);
+    }
+    record.amount_paid += gross;
+    total_net
+}
+
+/// Refund what is left of the deposit, mark the stream closed and free the
+/// listener/artist pair. Returns the refunded amount.
+fn close_stream(
+    env: &Env,
+    config: &StreamConfig,
+    record: &mut StreamRecord,
+    status: StreamStatus,
+    closed_at: u64,
+) -> i128 {
+    let refund = record.deposited_amount - record.amount_paid;
+    if refund > 0 {
+        let token_client = token::Client::new(env, &config.token);
+        token_client.transfer(&env.current_contract_address(), &record.listener, &refund);
+    }
+
+    record.status = status.clone();
+    record.stopped_at = Some(closed_at);
+    for recipient in record.recipients.iter() {
+        storage::remove_active_stream(env, &record.listener, &recipient.artist);
+    }
+
+    match status {
+        StreamStatus::Expired => event::stream_expired(
+            env,
+            &record.stream_id,
+            &record.listener,
+            &record.artist,
+            record.amount_paid,
+            refund,
+        ),
+        StreamStatus::Interrupted => event::stream_interrupted(
+            env,
+            &record.stream_id,
+            &record.listener,
+            &record.artist,
+            record.amount_paid,
+        ),
+        _ => event::stream_stopped(
+            env,
+            &record.stream_id,
+            &record.listener,
+            &record.artist,
+            record.amount_paid,
+            refund,
+        ),
+    }
+    refund
+}
+
+fn stream_id_from_count(env: &Env, count: u32) -> String {
+    let mut buffer = [0u8; 10];
+    let mut num = count;
+    let mut len = 0;
+    loop {
+        buffer[len] = b'0' + (num % 10) as u8;
+        num /= 10;
+        len += 1;
+        if num == 0 {
+            break;
+        }
+    }
+    buffer[..len].reverse();
+    String::from_bytes(env, &buffer[..len])
+}
+
+#[cfg(test)]
+mod test;
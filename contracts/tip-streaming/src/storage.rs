@@ -15,12 +15,12 @@ pub fn set_config(env: &Env, config: &StreamConfig) {
 }
 
 pub fn get_stream(env: &Env, stream_id: &String) -> Option<StreamRecord> {
-    let key = (STREAM_PREFIX, stream_id);
+    let key = (STREAM_PREFIX, stream_id.clone());
     env.storage().persistent().get(&key)
 }
 
 pub fn set_stream(env: &Env, stream_id: &String, record: &StreamRecord) {
-    let key = (STREAM_PREFIX, stream_id);
+    let key = (STREAM_PREFIX, stream_id.clone());
     env.storage().persistent().set(&key, record);
 }
 
@@ -0,0 +1,403 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token, vec, Address, Env, String, Vec,
+};
+
+struct Setup {
+    env: Env,
+    client: TipStreamingContractClient<'static>,
+    listener: Address,
+    artist: Address,
+    fee_collector: Address,
+    token: token::Client<'static>,
+}
+
+#[allow(deprecated)]
+fn setup() -> Setup {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, TipStreamingContract);
+    let client = TipStreamingContractClient::new(&env, &contract_id);
+
+    let token_admin = Address::generate(&env);
+    let token_id = env.register_stellar_asset_contract(token_admin);
+    let token = token::Client::new(&env, &token_id);
+    let token_admin_client = token::StellarAssetClient::new(&env, &token_id);
+
+    let admin = Address::generate(&env);
+    let listener = Address::generate(&env);
+    let artist = Address::generate(&env);
+    let fee_collector = Address::generate(&env);
+    token_admin_client.mint(&listener, &100_000);
+
+    client.initialize(
+        &admin,
+        &token_id,
+        &fee_collector,
+        &100, // 1%
+        &10,
+        &5_000,
+        &1_000,
+    );
+
+    Setup {
+        env,
+        client,
+        listener,
+        artist,
+        fee_collector,
+        token,
+    }
+}
+
+fn advance_time(env: &Env, seconds: u64) {
+    env.ledger().with_mut(|li| {
+        li.timestamp += seconds;
+    });
+}
+
+#[test]
+fn test_start_stream_escrows_deposit() {
+    let s = setup();
+    let stream_id = s.client.start_stream(&s.listener, &s.artist, &100, &10_000);
+
+    assert_eq!(stream_id, String::from_str(&s.env, "1"));
+    assert_eq!(s.token.balance(&s.listener), 90_000);
+    assert_eq!(s.token.balance(&s.client.address), 10_000);
+    assert_eq!(
+        s.client.get_active_stream(&s.listener, &s.artist),
+        Some(stream_id.clone())
+    );
+
+    let record = s.client.get_stream(&stream_id);
+    assert_eq!(record.status, StreamStatus::Active);
+    assert_eq!(record.deposited_amount, 10_000);
+    assert_eq!(record.amount_paid, 0);
+}
+
+#[test]
+fn test_start_stream_validation() {
+    let s = setup();
+    assert_eq!(
+        s.client.try_start_stream(&s.listener, &s.listener, &100, &10_000),
+        Err(Ok(Error::SameListenerArtist))
+    );
+    assert_eq!(
+        s.client.try_start_stream(&s.listener, &s.artist, &9, &10_000),
+        Err(Ok(Error::RateTooLow))
+    );
+    assert_eq!(
+        s.client.try_start_stream(&s.listener, &s.artist, &5_001, &10_000),
+        Err(Ok(Error::RateTooHigh))
+    );
+    assert_eq!(
+        s.client.try_start_stream(&s.listener, &s.artist, &100, &999),
+        Err(Ok(Error::InvalidAmount))
+    );
+    assert_eq!(
+        s.client.try_start_stream(&s.listener, &s.artist, &2_000, &1_500),
+        Err(Ok(Error::BalanceBelowMinimum))
+    );
+    assert_eq!(
+        s.client.try_start_stream(&s.listener, &s.artist, &100, &200_000),
+        Err(Ok(Error::InsufficientBalance))
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #5)")]
+fn test_one_active_stream_per_pair() {
+    let s = setup();
+    s.client.start_stream(&s.listener, &s.artist, &100, &10_000);
+    s.client.start_stream(&s.listener, &s.artist, &50, &5_000);
+}
+
+#[test]
+fn test_settle_pays_elapsed_time_minus_fee() {
+    let s = setup();
+    let stream_id = s.client.start_stream(&s.listener, &s.artist, &100, &10_000);
+
+    advance_time(&s.env, 30);
+    assert_eq!(s.client.get_accrued(&stream_id), 3_000);
+    let net = s.client.settle(&stream_id);
+
+    assert_eq!(net, 2_970);
+    assert_eq!(s.token.balance(&s.artist), 2_970);
+    assert_eq!(s.token.balance(&s.fee_collector), 30);
+
+    let record = s.client.get_stream(&stream_id);
+    assert_eq!(record.amount_paid, 3_000);
+    assert_eq!(record.last_settled_at, s.env.ledger().timestamp());
+
+    // Nothing more is owed until time passes again.
+    assert_eq!(s.client.settle(&stream_id), 0);
+}
+
+#[test]
+fn test_settle_expires_depleted_stream() {
+    let s = setup();
+    let stream_id = s.client.start_stream(&s.listener, &s.artist, &100, &10_000);
+    let started_at = s.env.ledger().timestamp();
+
+    advance_time(&s.env, 500);
+    s.client.settle(&stream_id);
+
+    let record = s.client.get_stream(&stream_id);
+    assert_eq!(record.status, StreamStatus::Expired);
+    assert_eq!(record.amount_paid, 10_000);
+    assert_eq!(record.stopped_at, Some(started_at + 100));
+    assert_eq!(s.token.balance(&s.client.address), 0);
+    assert_eq!(s.client.get_active_stream(&s.listener, &s.artist), None);
+
+    assert_eq!(s.client.try_settle(&stream_id), Err(Ok(Error::StreamExpired)));
+}
+
+#[test]
+fn test_expiry_settles_exactly_to_depletion_and_refunds_dust() {
+    let s = setup();
+    // 1_000 covers three full seconds at 300/s; 100 is left over.
+    let stream_id = s.client.start_stream(&s.listener, &s.artist, &300, &1_000);
+    let started_at = s.env.ledger().timestamp();
+
+    advance_time(&s.env, 2);
+    assert_eq!(s.client.get_accrued(&stream_id), 600);
+    advance_time(&s.env, 60);
+    assert_eq!(s.client.get_accrued(&stream_id), 900);
+
+    let net = s.client.settle(&stream_id);
+    assert_eq!(net, 891);
+    assert_eq!(s.token.balance(&s.artist), 891);
+    assert_eq!(s.token.balance(&s.fee_collector), 9);
+    assert_eq!(s.token.balance(&s.listener), 99_100);
+
+    let record = s.client.get_stream(&stream_id);
+    assert_eq!(record.status, StreamStatus::Expired);
+    assert_eq!(record.amount_paid, 900);
+    assert_eq!(record.last_settled_at, started_at + 3);
+    assert_eq!(record.stopped_at, Some(started_at + 3));
+}
+
+#[test]
+fn test_depleted_stream_cannot_be_topped_up() {
+    let s = setup();
+    let stream_id = s.client.start_stream(&s.listener, &s.artist, &100, &10_000);
+
+    advance_time(&s.env, 100);
+    assert_eq!(s.client.get_stream(&stream_id).status, StreamStatus::Expired);
+    assert_eq!(s.client.get_active_stream(&s.listener, &s.artist), None);
+
+    // The top-up expires the stream instead, paying the artist and taking nothing.
+    assert_eq!(s.client.top_up(&stream_id, &5_000), 0);
+    assert_eq!(s.token.balance(&s.listener), 90_000);
+    assert_eq!(s.token.balance(&s.artist), 9_900);
+    assert_eq!(s.client.get_stream(&stream_id).amount_paid, 10_000);
+    assert_eq!(
+        s.client.try_top_up(&stream_id, &5_000),
+        Err(Ok(Error::StreamExpired))
+    );
+}
+
+#[test]
+fn test_depleted_stream_frees_pair_for_new_stream() {
+    let s = setup();
+    let first_id = s.client.start_stream(&s.listener, &s.artist, &100, &10_000);
+
+    advance_time(&s.env, 50);
+    assert_eq!(
+        s.client.try_start_stream(&s.listener, &s.artist, &100, &10_000),
+        Err(Ok(Error::StreamAlreadyActive))
+    );
+
+    advance_time(&s.env, 50);
+    let second_id = s.client.start_stream(&s.listener, &s.artist, &100, &10_000);
+
+    assert_eq!(s.client.get_stream(&first_id).status, StreamStatus::Expired);
+    assert_eq!(s.client.get_active_stream(&s.listener, &s.artist), Some(second_id));
+    assert_eq!(s.token.balance(&s.artist), 9_900);
+}
+
+#[test]
+fn test_stop_after_depletion_marks_expired() {
+    let s = setup();
+    let stream_id = s.client.start_stream(&s.listener, &s.artist, &100, &10_000);
+
+    advance_time(&s.env, 150);
+    let refund = s.client.stop_stream(&stream_id);
+
+    assert_eq!(refund, 0);
+    assert_eq!(s.client.get_stream(&stream_id).status, StreamStatus::Expired);
+}
+
+#[test]
+fn test_interrupt_stream_is_permissionless() {
+    let s = setup();
+    let stream_id = s.client.start_stream(&s.listener, &s.artist, &100, &10_000);
+
+    advance_time(&s.env, 25);
+    let refund = s.client.interrupt_stream(&stream_id);
+    assert!(s.env.auths().is_empty());
+
+    assert_eq!(refund, 7_500);
+    assert_eq!(s.token.balance(&s.listener), 97_500);
+    assert_eq!(s.token.balance(&s.artist), 2_475);
+
+    let record = s.client.get_stream(&stream_id);
+    assert_eq!(record.status, StreamStatus::Interrupted);
+    assert_eq!(record.stopped_at, Some(s.env.ledger().timestamp()));
+    assert_eq!(s.client.get_active_stream(&s.listener, &s.artist), None);
+    assert_eq!(
+        s.client.try_interrupt_stream(&stream_id),
+        Err(Ok(Error::StreamAlreadyStopped))
+    );
+}
+
+#[test]
+fn test_top_up_extends_stream() {
+    let s = setup();
+    let stream_id = s.client.start_stream(&s.listener, &s.artist, &100, &10_000);
+
+    assert_eq!(s.client.top_up(&stream_id, &5_000), 5_000);
+    assert_eq!(s.client.get_stream(&stream_id).deposited_amount, 15_000);
+    assert_eq!(s.token.balance(&s.listener), 85_000);
+
+    advance_time(&s.env, 120);
+    s.client.settle(&stream_id);
+    assert_eq!(s.client.get_stream(&stream_id).amount_paid, 12_000);
+}
+
+#[test]
+fn test_stop_stream_refunds_unused_deposit() {
+    let s = setup();
+    let stream_id = s.client.start_stream(&s.listener, &s.artist, &100, &10_000);
+
+    advance_time(&s.env, 40);
+    let refund = s.client.stop_stream(&stream_id);
+
+    assert_eq!(refund, 6_000);
+    assert_eq!(s.token.balance(&s.listener), 96_000);
+    assert_eq!(s.token.balance(&s.artist), 3_960);
+    assert_eq!(s.token.balance(&s.fee_collector), 40);
+    assert_eq!(s.token.balance(&s.client.address), 0);
+
+    let record = s.client.get_stream(&stream_id);
+    assert_eq!(record.status, StreamStatus::Stopped);
+    assert_eq!(record.stopped_at, Some(s.env.ledger().timestamp()));
+    assert_eq!(s.client.get_active_stream(&s.listener, &s.artist), None);
+
+    assert_eq!(
+        s.client.try_settle(&stream_id),
+        Err(Ok(Error::StreamAlreadyStopped))
+    );
+
+    // The pair can stream again once the previous stream is closed.
+    let next_id = s.client.start_stream(&s.listener, &s.artist, &100, &10_000);
+    assert_eq!(next_id, String::from_str(&s.env, "2"));
+}
+
+fn playlist(s: &Setup) -> (Address, Address, Address, Vec<(Address, u32)>) {
+    let a = Address::generate(&s.env);
+    let b = Address::generate(&s.env);
+    let c = Address::generate(&s.env);
+    let artists = vec![
+        &s.env,
+        (a.clone(), 5_000),
+        (b.clone(), 3_000),
+        (c.clone(), 2_000),
+    ];
+    (a, b, c, artists)
+}
+
+#[test]
+fn test_playlist_stream_splits_settlement_by_weight() {
+    let s = setup();
+    let (a, b, c, artists) = playlist(&s);
+    let stream_id = s.client.start_playlist_stream(&s.listener, &artists, &100, &10_000);
+
+    let record = s.client.get_stream(&stream_id);
+    assert_eq!(record.mode, StreamMode::Playlist);
+    assert_eq!(record.artist, a);
+    assert_eq!(record.recipients.len(), 3);
+
+    advance_time(&s.env, 10);
+    let net = s.client.settle(&stream_id);
+
+    // 1_000 gross split 500 / 300 / 200, each less 1%.
+    assert_eq!(net, 990);
+    assert_eq!(s.token.balance(&a), 495);
+    assert_eq!(s.token.balance(&b), 297);
+    assert_eq!(s.token.balance(&c), 198);
+    assert_eq!(s.token.balance(&s.fee_collector), 10);
+
+    let record = s.client.get_stream(&stream_id);
+    assert_eq!(record.amount_paid, 1_000);
+    assert_eq!(record.recipients.get(0).unwrap().amount_paid, 500);
+    assert_eq!(record.recipients.get(1).unwrap().amount_paid, 300);
+    assert_eq!(record.recipients.get(2).unwrap().amount_paid, 200);
+}
+
+#[test]
+fn test_playlist_rounding_goes_to_last_artist() {
+    let s = setup();
+    let a = Address::generate(&s.env);
+    let b = Address::generate(&s.env);
+    let c = Address::generate(&s.env);
+    let artists = vec![&s.env, (a, 3_333), (b, 3_333), (c, 3_334)];
+    let stream_id = s.client.start_playlist_stream(&s.listener, &artists, &10, &1_000);
+
+    advance_time(&s.env, 10);
+    s.client.settle(&stream_id);
+
+    let record = s.client.get_stream(&stream_id);
+    assert_eq!(record.recipients.get(0).unwrap().amount_paid, 33);
+    assert_eq!(record.recipients.get(1).unwrap().amount_paid, 33);
+    assert_eq!(record.recipients.get(2).unwrap().amount_paid, 34);
+}
+
+#[test]
+fn test_playlist_stream_blocks_each_pair_until_stopped() {
+    let s = setup();
+    let (_, b, c, artists) = playlist(&s);
+    let stream_id = s.client.start_playlist_stream(&s.listener, &artists, &100, &10_000);
+
+    assert_eq!(s.client.get_active_stream(&s.listener, &c), Some(stream_id.clone()));
+    assert_eq!(
+        s.client.try_start_stream(&s.listener, &b, &100, &10_000),
+        Err(Ok(Error::StreamAlreadyActive))
+    );
+
+    advance_time(&s.env, 20);
+    let refund = s.client.stop_stream(&stream_id);
+    assert_eq!(refund, 8_000);
+    assert_eq!(s.client.get_active_stream(&s.listener, &c), None);
+    s.client.start_stream(&s.listener, &b, &100, &10_000);
+}
+
+#[test]
+fn test_playlist_stream_validation() {
+    let s = setup();
+    let a = Address::generate(&s.env);
+    let b = Address::generate(&s.env);
+
+    let cases = [
+        vec![&s.env],
+        vec![&s.env, (a.clone(), 6_000), (b.clone(), 3_000)],
+        vec![&s.env, (a.clone(), 10_000), (b.clone(), 0)],
+        vec![&s.env, (a.clone(), 5_000), (a.clone(), 5_000)],
+    ];
+    for artists in cases.iter() {
+        assert_eq!(
+            s.client.try_start_playlist_stream(&s.listener, artists, &100, &10_000),
+            Err(Ok(Error::InvalidSplit))
+        );
+    }
+
+    let with_listener = vec![&s.env, (a, 5_000), (s.listener.clone(), 5_000)];
+    assert_eq!(
+        s.client.try_start_playlist_stream(&s.listener, &with_listener, &100, &10_000),
+        Err(Ok(Error::SameListenerArtist))
+    );
+}
@@ -1,4 +1,4 @@
-use soroban_sdk::{contracttype, Address, String};
+use soroban_sdk::{contracttype, Address, String, Vec};
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -9,6 +9,23 @@ pub enum StreamStatus {
     Expired,
 }
 
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum StreamMode {
+    Single,
+    Playlist,
+}
+
+/// An artist paid by a stream. `amount_paid` is this artist's gross share of
+/// the stream's `amount_paid`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StreamRecipient {
+    pub artist: Address,
+    pub weight_basis_points: u32,
+    pub amount_paid: i128,
+}
+
 #[contracttype]
 #[derive(Clone, Debug)]
 pub struct StreamRecord {
@@ -22,6 +39,8 @@ pub struct StreamRecord {
     pub stopped_at: Option<u64>,
     pub last_settled_at: u64,
     pub status: StreamStatus,
+    pub mode: StreamMode,
+    pub recipients: Vec<StreamRecipient>, // `artist` is the first recipient
 }
 
 #[contracttype]
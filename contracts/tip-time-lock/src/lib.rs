@@ -6,7 +6,9 @@ mod types;
 #[cfg(test)]
 mod test;
 
-use soroban_sdk::{contract, contractimpl, symbol_short, token, Address, Env, String, Vec};
+use soroban_sdk::{
+    contract, contractimpl, contracttype, symbol_short, token, Address, Env, String, Vec,
+};
 use types::{Asset, Error, TimeLockStatus, TimeLockTip};
 
 #[contracttype]
@@ -26,6 +28,7 @@ pub struct TipActionEvent {
 }
 
 impl TipActionEvent {
+    #[allow(clippy::too_many_arguments)]
     fn new(
         env: &Env,
         action: &str,
@@ -60,12 +63,36 @@ pub struct TimeLockContract;
 
 #[contractimpl]
 impl TimeLockContract {
+    /// Set the admin and the Stellar Asset Contract used for native XLM tips
+    pub fn initialize(env: Env, admin: Address, native_token: Address) -> Result<(), Error> {
+        if storage::has_admin(&env) {
+            return Err(Error::AlreadyInitialized);
+        }
+        admin.require_auth();
+
+        storage::set_admin(&env, &admin);
+        storage::set_native_token(&env, &native_token);
+        Ok(())
+    }
+
+    /// Point native XLM tips at a different Stellar Asset Contract (admin only).
+    /// Only tips created afterwards use it; tips already locked are claimed
+    /// and refunded from the contract they were locked in.
+    pub fn set_native_token(env: Env, native_token: Address) -> Result<(), Error> {
+        let admin = storage::get_admin(&env).ok_or(Error::NotInitialized)?;
+        admin.require_auth();
+
+        storage::set_native_token(&env, &native_token);
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub fn create_time_lock_tip(
         env: Env,
         tipper: Address,
         artist: Address,
         amount: i128,
-        asset_address: Address,
+        asset: Asset,
         unlock_time: u64,
         message: String,
         nonce: u64,
@@ -89,8 +116,8 @@ impl TimeLockContract {
         }
 
         // Lock funds inside contract
-        let token_client = token::Client::new(&env, &asset_address);
-        token_client.transfer(&tipper, &env.current_contract_address(), &amount);
+        let token = resolve_asset(&env, &asset)?;
+        token::Client::new(&env, &token).transfer(&tipper, &env.current_contract_address(), &amount);
 
         let counter = storage::increment_counter(&env);
         
@@ -116,7 +143,8 @@ impl TimeLockContract {
             tipper,
             artist,
             amount,
-            asset: Asset::Token(asset_address),
+            asset,
+            token,
             unlock_time,
             message,
             status: TimeLockStatus::Locked,
@@ -180,12 +208,8 @@ impl TimeLockContract {
         storage::update_tip(&env, &tip);
 
         // Transfer funds to artist
-        match &tip.asset {
-            Asset::Token(token_address) => {
-                let token_client = token::Client::new(&env, token_address);
-                token_client.transfer(&env.current_contract_address(), &artist, &tip.amount);
-            }
-        }
+        let token_client = token::Client::new(&env, &tip.token);
+        token_client.transfer(&env.current_contract_address(), &artist, &tip.amount);
 
         // Emit canonical tip action event for claim (execute)
         env.events().publish(
@@ -244,12 +268,8 @@ impl TimeLockContract {
         storage::update_tip(&env, &tip);
 
         // Transfer funds back to tipper
-        match &tip.asset {
-            Asset::Token(token_address) => {
-                let token_client = token::Client::new(&env, token_address);
-                token_client.transfer(&env.current_contract_address(), &tipper, &tip.amount);
-            }
-        }
+        let token_client = token::Client::new(&env, &tip.token);
+        token_client.transfer(&env.current_contract_address(), &tipper, &tip.amount);
 
         // Emit canonical tip action event for refund (cancel)
         env.events().publish(
@@ -288,3 +308,12 @@ impl TimeLockContract {
         pending
     }
 }
+
+/// Token contract a tip in `asset` is locked in. `Asset::Native` maps to the
+/// XLM contract the admin configured.
+fn resolve_asset(env: &Env, asset: &Asset) -> Result<Address, Error> {
+    match asset {
+        Asset::Native => storage::get_native_token(env).ok_or(Error::NativeAssetNotConfigured),
+        Asset::Token(token_address) => Ok(token_address.clone()),
+    }
+}
@@ -34,3 +34,23 @@ pub fn increment_counter(env: &Env) -> u32 {
     env.storage().instance().set(&key, &counter);
     counter
 }
+
+pub fn get_native_token(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&DataKey::NativeToken)
+}
+
+pub fn set_native_token(env: &Env, native_token: &Address) {
+    env.storage().instance().set(&DataKey::NativeToken, native_token);
+}
+
+pub fn get_admin(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&DataKey::Admin)
+}
+
+pub fn has_admin(env: &Env) -> bool {
+    env.storage().instance().has(&DataKey::Admin)
+}
+
+pub fn set_admin(env: &Env, admin: &Address) {
+    env.storage().instance().set(&DataKey::Admin, admin);
+}
@@ -36,7 +36,7 @@ fn test_tip_lifecycle() {
     let amount = 100;
     let message = String::from_str(&env, "Happy Birthday!");
 
-    let lock_id = client.create_time_lock_tip(&tipper, &artist, &amount, &token.address, &unlock_time, &message, &1);
+    let lock_id = client.create_time_lock_tip(&tipper, &artist, &amount, &Asset::Token(token.address.clone()), &unlock_time, &message, &1);
 
     // Check balance
     assert_eq!(token.balance(&tipper), 900);
@@ -83,7 +83,7 @@ fn test_refund_lifecycle() {
     let amount = 100;
     let message = String::from_str(&env, "Testing refund");
 
-    let lock_id = client.create_time_lock_tip(&tipper, &artist, &amount, &token.address, &unlock_time, &message, &1);
+    let lock_id = client.create_time_lock_tip(&tipper, &artist, &amount, &Asset::Token(token.address.clone()), &unlock_time, &message, &1);
 
     // Try to refund before 30 days
     env.ledger().set_timestamp(unlock_time + 100);
@@ -120,8 +120,8 @@ fn test_get_pending_tips() {
     let current_time = 10000;
     env.ledger().set_timestamp(current_time);
 
-    client.create_time_lock_tip(&tipper, &artist, &100, &token.address, &(current_time + 1000), &String::from_str(&env, "Tip 1"), &1);
-    client.create_time_lock_tip(&tipper, &artist, &200, &token.address, &(current_time + 2000), &String::from_str(&env, "Tip 2"), &2);
+    client.create_time_lock_tip(&tipper, &artist, &100, &Asset::Token(token.address.clone()), &(current_time + 1000), &String::from_str(&env, "Tip 1"), &1);
+    client.create_time_lock_tip(&tipper, &artist, &200, &Asset::Token(token.address.clone()), &(current_time + 2000), &String::from_str(&env, "Tip 2"), &2);
 
     let pending = client.get_pending_tips(&artist);
     assert_eq!(pending.len(), 2);
@@ -151,8 +151,8 @@ fn test_replay_create_time_lock_tip_fails() {
     let amount = 100;
     let message = String::from_str(&env, "Test");
 
-    client.create_time_lock_tip(&tipper, &artist, &amount, &token.address, &unlock_time, &message, &1);
-    let result = client.try_create_time_lock_tip(&tipper, &artist, &amount, &token.address, &unlock_time, &message, &1);
+    client.create_time_lock_tip(&tipper, &artist, &amount, &Asset::Token(token.address.clone()), &unlock_time, &message, &1);
+    let result = client.try_create_time_lock_tip(&tipper, &artist, &amount, &Asset::Token(token.address.clone()), &unlock_time, &message, &1);
     assert!(result.is_err());
 }
 
@@ -178,7 +178,7 @@ fn test_replay_claim_tip_fails() {
     let amount = 100;
     let message = String::from_str(&env, "Test");
 
-    let lock_id = client.create_time_lock_tip(&tipper, &artist, &amount, &token.address, &unlock_time, &message, &1);
+    let lock_id = client.create_time_lock_tip(&tipper, &artist, &amount, &Asset::Token(token.address.clone()), &unlock_time, &message, &1);
 
     // Advance time
     env.ledger().set_timestamp(unlock_time);
@@ -210,7 +210,7 @@ fn test_replay_refund_tip_fails() {
     let amount = 100;
     let message = String::from_str(&env, "Test");
 
-    let lock_id = client.create_time_lock_tip(&tipper, &artist, &amount, &token.address, &unlock_time, &message, &1);
+    let lock_id = client.create_time_lock_tip(&tipper, &artist, &amount, &Asset::Token(token.address.clone()), &unlock_time, &message, &1);
 
     // Advance time for refund
     let thirty_days = 30 * 24 * 60 * 60;
@@ -220,3 +220,51 @@ fn test_replay_refund_tip_fails() {
     let result = client.try_refund_tip(&lock_id, &tipper, &2);
     assert!(result.is_err());
 }
+
+#[test]
+fn test_native_tip_lifecycle() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, TimeLockContract);
+    let client = TimeLockContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let tipper = Address::generate(&env);
+    let artist = Address::generate(&env);
+
+    let current_time = 10000;
+    env.ledger().set_timestamp(current_time);
+    let message = String::from_str(&env, "Native tip");
+
+    let result = client.try_create_time_lock_tip(&tipper, &artist, &100, &Asset::Native, &(current_time + 1000), &message, &1);
+    assert_eq!(result, Err(Ok(Error::NativeAssetNotConfigured)));
+
+    // Stand-in for the XLM Stellar Asset Contract
+    let (xlm, xlm_admin) = create_token_contract(&env, &admin);
+    xlm_admin.mint(&tipper, &1000);
+    client.initialize(&admin, &xlm.address);
+
+    let lock_id = client.create_time_lock_tip(&tipper, &artist, &100, &Asset::Native, &(current_time + 1000), &message, &2);
+    assert_eq!(xlm.balance(&contract_id), 100);
+
+    env.ledger().set_timestamp(current_time + 1000);
+    client.claim_tip(&lock_id, &artist, &1);
+    assert_eq!(xlm.balance(&artist), 100);
+
+    // The admin can point native tips at a replacement contract
+    let (new_xlm, new_xlm_admin) = create_token_contract(&env, &admin);
+    new_xlm_admin.mint(&tipper, &50);
+    new_xlm_admin.mint(&contract_id, &1000);
+    let old_lock_id = client.create_time_lock_tip(&tipper, &artist, &200, &Asset::Native, &(current_time + 2000), &message, &3);
+    client.set_native_token(&new_xlm.address);
+    client.create_time_lock_tip(&tipper, &artist, &50, &Asset::Native, &(current_time + 2000), &message, &4);
+    assert_eq!(new_xlm.balance(&contract_id), 1050);
+
+    // A tip locked before the swap is still paid from the old contract
+    env.ledger().set_timestamp(current_time + 2000);
+    client.claim_tip(&old_lock_id, &artist, &2);
+    assert_eq!(xlm.balance(&artist), 300);
+    assert_eq!(xlm.balance(&contract_id), 0);
+    assert_eq!(new_xlm.balance(&contract_id), 1050);
+}
@@ -12,11 +12,15 @@ pub enum Error {
     InvalidUnlockTime = 6,
     RefundNotAvailableYet = 7,
     InvalidNonce = 8,
+    AlreadyInitialized = 9,
+    NativeAssetNotConfigured = 10,
+    NotInitialized = 11,
 }
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum Asset {
+    Native, // XLM, locked through the admin-configured native token
     Token(Address),
 }
 
@@ -36,6 +40,7 @@ pub struct TimeLockTip {
     pub artist: Address,
     pub amount: i128,
     pub asset: Asset,
+    pub token: Address, // Token contract `asset` resolved to when the tip was locked
     pub unlock_time: u64,
     pub message: String,
     pub status: TimeLockStatus,
@@ -48,4 +53,6 @@ pub enum DataKey {
     ArtistTips(Address),
     Counter,
     ActorNonce(Address),
+    Admin,
+    NativeToken,
 }